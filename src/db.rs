@@ -78,15 +78,15 @@ pub fn seed_db(logger: &Logger, pool: &Pool) -> Result<(), MyError> {
 
         for floor in 2..8 {
             // no meeting rooms on first floor
-            // Add 3 conference rooms per-floor
+            // Add 3 conference rooms per-floor, from small to large
             let code = format!("{}A", floor);
-            let _ = Room::add_room(b.id, code, floor, logger, &tx)?;
+            let _ = Room::add_room(b.id, code, floor, 4, logger, &tx)?;
 
             let code = format!("{}B", floor);
-            let _ = Room::add_room(b.id, code, floor, logger, &tx)?;
+            let _ = Room::add_room(b.id, code, floor, 8, logger, &tx)?;
 
             let code = format!("{}C", floor);
-            let _ = Room::add_room(b.id, code, floor, logger, &tx)?;
+            let _ = Room::add_room(b.id, code, floor, 16, logger, &tx)?;
         }
     }
 
@@ -1,6 +1,7 @@
 use postgres::error::Error as PGError;
 use r2d2::Error as PoolError;
 use std::fmt;
+use uuid::Uuid;
 
 #[derive(Debug)]
 pub enum DBError {
@@ -12,13 +13,33 @@ pub enum DBError {
 #[derive(Debug)]
 pub enum MeetingError {
     ScheduleConflict,
+    NotFound,
+    NotPermitted,
+    AttendeeConflict { user: Uuid, conflicting_meeting: Uuid },
+    CapacityExceeded { capacity: i32, headcount: i32 },
+    HoldExpired,
+    NoShowReleased,
+    /// Too early to check in, or the grace period is up
+    OutsideCheckInWindow,
+    OutsideOpeningHours,
+    RoomNotFound,
+    UnknownUser,
+    /// The room is blacked out, e.g. for maintenance
+    RoomUnavailable,
+    /// The room lacks these required amenities, by code
+    MissingAmenities { missing: Vec<String> },
+    /// Longer than the room's type allows
+    BookingTooLong { max_minutes: i32 },
+    /// Further ahead than the room's type allows
+    OutsideBookingWindow { advance_days: i32 },
 }
 
 #[derive(Debug)]
 pub enum MyError {
     DBError(DBError),
     MeetingError(MeetingError),
-    ValueError,
+    /// Invalid user input, with the index of the offending item for batches
+    ValueError(Option<usize>),
 }
 
 impl fmt::Display for MyError {
@@ -26,7 +47,8 @@ impl fmt::Display for MyError {
         match self {
             &MyError::DBError(ref err) => write!(f, "DB Error: {:?}", err),
             &MyError::MeetingError(ref err) => write!(f, "Meeting Error: {:?}", err),
-            &MyError::ValueError => write!(f, "Value Error"),
+            &MyError::ValueError(None) => write!(f, "Value Error"),
+            &MyError::ValueError(Some(index)) => write!(f, "Value Error at index {}", index),
         }
     }
 }
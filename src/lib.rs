@@ -16,3 +16,5 @@ pub mod db;
 pub mod errors;
 pub mod log;
 pub mod models;
+pub mod recurrence;
+pub mod timeslots;
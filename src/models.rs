@@ -1,6 +1,6 @@
-use chrono::prelude::*;
+use chrono::{prelude::*, Duration};
 use postgres::{
-    error::EXCLUSION_VIOLATION,
+    error::{Error as PGError, EXCLUSION_VIOLATION},
     rows::{Row, Rows},
     transaction::Transaction,
 };
@@ -9,8 +9,113 @@ use uuid::Uuid;
 
 use db::TSTZRange;
 use errors::{DBError, MeetingError, MyError};
+use recurrence::{Frequency, Recurrence};
+use timeslots::{self, WorkingHours};
 
+/// How many minutes before its start a meeting opens for check-in.
+pub const CHECKIN_OPENS_MINUTES: i32 = 15;
 
+/// How many minutes apart the start times suggested by `Meeting::suggest_slots`
+/// are.
+pub const SUGGESTION_STEP_MINUTES: i64 = 15;
+
+/// Parse a user-provided timestamp, `field` naming it in the log.
+///
+/// Besides RFC 3339, a wall-clock time without an offset, such as
+/// `2018-10-10T09:00:00`, is accepted as local time in the given building.
+/// Wall-clock times skipped when DST starts are rejected, while those that
+/// occur twice when DST ends are resolved as PostgreSQL does; an offset picks
+/// the other one.
+fn parse_timestamp(dt: &str,
+                   field: &str,
+                   bldg_ext_id: Uuid,
+                   logger: &Logger,
+                   tx: &Transaction)
+                   -> Result<DateTime<Utc>, MyError> {
+    if let Ok(dt) = dt.parse::<DateTime<Utc>>() {
+        return Ok(dt);
+    }
+
+    let local_dt = dt.parse::<NaiveDateTime>().map_err(|_| {
+        info!(logger, "Failed to convert user-provided {}", field);
+        MyError::ValueError(None)
+    })?;
+
+    let stmt = "
+	SELECT $2::timestamp AT TIME ZONE b.timezone,
+		   ($2::timestamp AT TIME ZONE b.timezone) AT TIME ZONE b.timezone = $2::timestamp
+	  FROM testing.building b
+	 WHERE b.ext_id = $1;";
+
+    let rows = tx.query(stmt, &[&bldg_ext_id, &local_dt]).map_err(|err| {
+        error!(logger, "Failed to convert local time: DB Error.";
+				"step"=>"parse_timestamp", "err"=>err.to_string());
+        MyError::DBError(DBError::PGError(err))
+    })?;
+
+    match rows.iter().next() {
+        Some(row) => {
+            let exists: bool = row.get(1);
+            if !exists {
+                info!(logger, "User-provided {} does not exist in the building's timezone", field);
+                return Err(MyError::ValueError(None));
+            }
+            Ok(row.get(0))
+        }
+        None => {
+            info!(logger, "No such building: {}", bldg_ext_id);
+            Err(MyError::DBError(DBError::NoRecord))
+        }
+    }
+}
+
+/// Parse user-provided start and end timestamps into the time slot of a
+/// booking in the given building, per `parse_timestamp`.
+fn parse_time_slot(start_dt: &str,
+                   end_dt: &str,
+                   bldg_ext_id: Uuid,
+                   logger: &Logger,
+                   tx: &Transaction)
+                   -> Result<TSTZRange, MyError> {
+    let start_dt = parse_timestamp(start_dt, "start_dt", bldg_ext_id, logger, tx)?;
+    let end_dt = parse_timestamp(end_dt, "end_dt", bldg_ext_id, logger, tx)?;
+
+    if end_dt <= start_dt {
+        info!(logger, "User-provided end_dt is not after start_dt");
+        return Err(MyError::ValueError(None));
+    }
+
+    Ok(timeslots::slot(start_dt, end_dt))
+}
+
+/// The instant `utc` with the offset of the timezone in which it reads as the
+/// wall-clock time `local`.  Fails if the two are a day or more apart.
+fn to_local(utc: DateTime<Utc>, local: NaiveDateTime) -> Result<DateTime<FixedOffset>, MyError> {
+    let offset = FixedOffset::east_opt((local - utc.naive_utc()).num_seconds() as i32)
+                     .ok_or(MyError::ValueError(None))?;
+    Ok(offset.from_utc_datetime(&utc.naive_utc()))
+}
+
+/// Translate a failed meeting insert or update, surfacing overlaps with other
+/// bookings as a `ScheduleConflict` and with room blackouts as
+/// `RoomUnavailable`.
+fn map_booking_err(err: PGError, step: &'static str, logger: &Logger) -> MyError {
+    let blacked_out = err.as_db()
+                         .and_then(|db_err| db_err.constraint.as_ref())
+                         .is_some_and(|constraint| constraint == "room_blackout_overlap");
+
+    if blacked_out {
+        info!(logger, "Room is blacked out.  Could not schedule."; "step"=>step);
+        MyError::MeetingError(MeetingError::RoomUnavailable)
+    } else if Some(&EXCLUSION_VIOLATION) == err.code() {
+        info!(logger, "Meeting schedule overlap.  Could not schedule."; "step"=>step);
+        MyError::MeetingError(MeetingError::ScheduleConflict)
+    } else {
+        error!(logger, "Failed to book meeting: Unknown DB Error.";
+				"step"=>step, "err"=>err.to_string());
+        MyError::DBError(DBError::PGError(err))
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct User {
@@ -19,6 +124,7 @@ pub struct User {
     pub first_name: String,
     pub last_name: String,
     pub username: String,
+    pub is_admin: bool,
 }
 impl User {
     /// 'add_user' features a functional-style implementation
@@ -35,7 +141,8 @@ impl User {
 				  testing.users.ext_id,
 				  testing.users.first_name,
 				  testing.users.last_name,
-				  testing.users.username;";
+				  testing.users.username,
+				  testing.users.is_admin;";
 
         tx.query(stmt, &[&first_name, &last_name, &username])
           .map_err(|err| {
@@ -52,7 +159,8 @@ impl User {
                                              ext_id: row.get(1),
                                              first_name: row.get(2),
                                              last_name: row.get(3),
-                                             username: row.get(4), };
+                                             username: row.get(4),
+                                             is_admin: row.get(5), };
                            info!(logger, "Added user: {}", user.username);
                            user
                        })
@@ -67,7 +175,7 @@ impl User {
     pub fn get_users(logger: &Logger, tx: &Transaction)
                         -> Result<Vec<User>, MyError> {
         let stmt = "
-		SELECT users.id, ext_id, first_name, last_name, username
+		SELECT users.id, ext_id, first_name, last_name, username, is_admin
 		  FROM testing.users;";
 
         tx.query(stmt, &[])
@@ -88,11 +196,203 @@ impl User {
                                                      ext_id: row.get(1),
                                                      first_name: row.get(2),
                                                      last_name: row.get(3),
-                                                     username: row.get(4), })
+                                                     username: row.get(4),
+                                                     is_admin: row.get(5), })
                               .collect::<Vec<User>>();
               Ok(users)
           })
     }
+
+    /// Time within `window` when all of the given users are free, i.e. neither
+    /// organizing nor attending a meeting they have not declined.
+    ///
+    /// Free ranges shorter than `min_duration` are left out.  When a building
+    /// is given, the ranges are limited to its opening hours and each comes
+    /// with the best room there that is free for all of it, per
+    /// `Room::find_available`.  At least one user is required.
+    pub fn find_common_free_time(user_ext_ids: Vec<Uuid>,
+                                 window: TSTZRange,
+                                 min_duration: Option<Duration>,
+                                 bldg_ext_id: Option<Uuid>,
+                                 filters: &RoomFilter,
+                                 logger: &Logger,
+                                 tx: &Transaction)
+                                 -> Result<Vec<CommonFreeTime>, MyError> {
+        if user_ext_ids.is_empty() {
+            info!(logger, "Cannot find common free time of no users");
+            return Err(MyError::ValueError(None));
+        }
+
+        let stmt = "
+		SELECT (SELECT count(*)
+				  FROM testing.users u
+				 WHERE u.ext_id = ANY($1))
+			 = (SELECT count(DISTINCT user_ext_id)
+				  FROM unnest($1::uuid[]) user_ext_id);";
+
+        let rows = tx.query(stmt, &[&user_ext_ids]).map_err(|err| {
+            error!(logger, "Failed to look up users: DB Error.";
+					"step"=>"find_common_free_time", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let all_users_exist: bool = rows.get(0).get(0);
+        if !all_users_exist {
+            info!(logger, "Cannot find free time of unknown users");
+            return Err(MyError::DBError(DBError::NoRecord));
+        }
+
+        let stmt = "
+		SELECT mtg.time_slot * $2
+		  FROM testing.meeting mtg
+		 WHERE mtg.time_slot && $2
+		   AND (mtg.organizer_id IN (SELECT u.id
+									   FROM testing.users u
+									  WHERE u.ext_id = ANY($1))
+				OR EXISTS (SELECT true
+							 FROM testing.meeting_attendee a
+							 JOIN testing.users u
+							   ON a.user_id = u.id
+							WHERE a.meeting_id = mtg.id
+							  AND a.status <> 'declined'
+							  AND u.ext_id = ANY($1)));";
+
+        let rows = tx.query(stmt, &[&user_ext_ids, &window]).map_err(|err| {
+            error!(logger, "Failed to query for users' meetings: DB Error.";
+					"step"=>"find_common_free_time", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let busy = rows.iter().map(|row| row.get(0)).collect::<Vec<TSTZRange>>();
+
+        let free = timeslots::free_gaps(&window, &busy);
+        let free = match bldg_ext_id {
+            Some(bldg_ext_id) => {
+                let opening_windows =
+                    Building::get_opening_windows(bldg_ext_id, &window, logger, tx)?;
+                timeslots::mask(&free, &opening_windows)
+            }
+            None => free,
+        };
+
+        let mut candidates = Vec::new();
+        for time_slot in free {
+            if let Some(min_duration) = min_duration {
+                let long_enough = match (time_slot.lower(), time_slot.upper()) {
+                    (Some(lower), Some(upper)) => upper.value - lower.value >= min_duration,
+                    _ => true,
+                };
+                if !long_enough {
+                    continue;
+                }
+            }
+
+            let room = match bldg_ext_id {
+                Some(bldg_ext_id) => {
+                    Room::find_available(bldg_ext_id, time_slot, filters, logger, tx)?
+                        .into_iter()
+                        .next()
+                }
+                None => None,
+            };
+
+            candidates.push(CommonFreeTime { time_slot, room });
+        }
+
+        Ok(candidates)
+    }
+
+    /// Grant or revoke admin rights, which allow managing other users' meetings.
+    pub fn set_admin(username: String,
+                     is_admin: bool,
+                     logger: &Logger,
+                     tx: &Transaction)
+                     -> Result<User, MyError> {
+        let stmt = "
+		UPDATE testing.users
+		   SET is_admin = $2
+		 WHERE username = $1
+		RETURNING id, ext_id, first_name, last_name, username, is_admin;";
+
+        tx.query(stmt, &[&username, &is_admin])
+          .map_err(|err| {
+              error!(logger, "Failed to update user: DB Error.";
+                    "step"=>"set_admin", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })
+          .and_then(|rows: Rows| {
+              rows.into_iter()
+                  .next()
+                  .map(|row: Row| User { id: row.get(0),
+                                         ext_id: row.get(1),
+                                         first_name: row.get(2),
+                                         last_name: row.get(3),
+                                         username: row.get(4),
+                                         is_admin: row.get(5), })
+                  .ok_or_else(|| {
+                      info!(logger, "No such user: {}", username);
+                      MyError::DBError(DBError::NoRecord)
+                  })
+          })
+    }
+
+    fn get_id(username: &str, logger: &Logger, tx: &Transaction) -> Result<i64, MyError> {
+        let stmt = "
+		SELECT id
+		  FROM testing.users
+		 WHERE username = $1;";
+
+        let rows = tx.query(stmt, &[&username]).map_err(|err| {
+            error!(logger, "Failed to look up user: DB Error.";
+					"step"=>"get_id", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => Ok(row.get(0)),
+            None => {
+                info!(logger, "No such user: {}", username);
+                Err(MyError::MeetingError(MeetingError::UnknownUser))
+            }
+        }
+    }
+
+    /// Fail with `NotPermitted` unless the user is an administrator.
+    fn require_admin(user_id: i64, logger: &Logger, tx: &Transaction) -> Result<(), MyError> {
+        let stmt = "
+		SELECT is_admin
+		  FROM testing.users
+		 WHERE id = $1;";
+
+        let rows = tx.query(stmt, &[&user_id]).map_err(|err| {
+            error!(logger, "Failed to look up user: DB Error.";
+					"step"=>"require_admin", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let is_admin: bool = match rows.iter().next() {
+            Some(row) => row.get(0),
+            None => {
+                info!(logger, "No such user: {}", user_id);
+                return Err(MyError::MeetingError(MeetingError::UnknownUser));
+            }
+        };
+
+        if !is_admin {
+            info!(logger, "User {} is not an administrator", user_id);
+            return Err(MyError::MeetingError(MeetingError::NotPermitted));
+        }
+
+        Ok(())
+    }
+}
+
+/// A range of time when a group of users is free, with a room to meet in if
+/// one was asked for and found.
+#[derive(Debug)]
+pub struct CommonFreeTime {
+    pub time_slot: TSTZRange,
+    pub room: Option<Room>,
 }
 
 #[derive(Debug, Clone)]
@@ -100,6 +400,11 @@ pub struct Building {
     pub id: i64,
     pub ext_id: Uuid,
     pub name: String,
+    /// How long after a meeting starts its room is released unless someone
+    /// checks in
+    pub checkin_grace_minutes: i32,
+    /// IANA name of the building's timezone, e.g. `Europe/Berlin`
+    pub timezone: String,
 }
 impl Building {
     /// add_building features a procedural-style implementation
@@ -112,7 +417,9 @@ impl Building {
 		VALUES ($1)
 		RETURNING testing.building.id,
 				  testing.building.ext_id,
-				  testing.building.name;";
+				  testing.building.name,
+				  testing.building.checkin_grace_minutes,
+				  testing.building.timezone;";
 
         let result = tx.query(stmt, &[&name]).map_err(|err| {
             error!(logger, "Failed to add building: DB Error.";
@@ -131,7 +438,9 @@ impl Building {
         // example of referencing row elements by name
         let bldg = Building { id: row.get("id"),
                               ext_id: row.get("ext_id"),
-                              name: row.get("name"), };
+                              name: row.get("name"),
+                              checkin_grace_minutes: row.get("checkin_grace_minutes"),
+                              timezone: row.get("timezone"), };
 
         info!(logger, "Added building: {}", bldg.name);
 
@@ -141,7 +450,7 @@ impl Building {
     pub fn get_buildings(logger: &Logger, tx: &Transaction)
                          -> Result<Vec<Building>, MyError> {
         let stmt = "
-		SELECT id, ext_id, name
+		SELECT id, ext_id, name, checkin_grace_minutes, timezone
 		  FROM testing.building;";
 
         tx.query(stmt, &[])
@@ -160,11 +469,327 @@ impl Building {
               let bldgs = rows.into_iter()
                               .map(|row: Row| Building { id: row.get(0),
                                                          ext_id: row.get(1),
-                                                         name: row.get(2), })
+                                                         name: row.get(2),
+                                                         checkin_grace_minutes: row.get(3),
+                                                         timezone: row.get(4), })
                               .collect::<Vec<Building>>();
               Ok(bldgs)
           })
     }
+
+    /// Set how long meetings in the building may go without a check-in before
+    /// their room is released, see `Meeting::release_no_shows`.  The grace
+    /// period must be at least a minute.
+    pub fn set_checkin_grace(bldg_ext_id: Uuid,
+                             grace: Duration,
+                             logger: &Logger,
+                             tx: &Transaction)
+                             -> Result<Building, MyError> {
+        // a meeting released right at its start would be left without a time slot
+        if grace < Duration::minutes(1) {
+            info!(logger, "Check-in grace period must be at least a minute");
+            return Err(MyError::ValueError(None));
+        }
+        let grace_minutes = grace.num_minutes() as i32;
+
+        let stmt = "
+		UPDATE testing.building
+		   SET checkin_grace_minutes = $2
+		 WHERE ext_id = $1
+		RETURNING id, ext_id, name, checkin_grace_minutes, timezone;";
+
+        let rows = tx.query(stmt, &[&bldg_ext_id, &grace_minutes]).map_err(|err| {
+            error!(logger, "Failed to update building: DB Error.";
+					"step"=>"set_checkin_grace", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => Ok(Building { id: row.get(0),
+                                       ext_id: row.get(1),
+                                       name: row.get(2),
+                                       checkin_grace_minutes: row.get(3),
+                                       timezone: row.get(4), }),
+            None => {
+                info!(logger, "No such building: {}", bldg_ext_id);
+                Err(MyError::DBError(DBError::NoRecord))
+            }
+        }
+    }
+
+    /// Set the building's timezone, given by its IANA name.  Local times of
+    /// meetings in the building are updated to match, while opening hours
+    /// and closure days now follow the new timezone.
+    pub fn set_timezone(bldg_ext_id: Uuid,
+                        timezone: String,
+                        logger: &Logger,
+                        tx: &Transaction)
+                        -> Result<Building, MyError> {
+        let stmt = "
+		SELECT EXISTS (SELECT true
+						 FROM pg_timezone_names
+						WHERE name = $1);";
+
+        let rows = tx.query(stmt, &[&timezone]).map_err(|err| {
+            error!(logger, "Failed to look up timezone: DB Error.";
+					"step"=>"set_timezone", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let known: bool = rows.get(0).get(0);
+        if !known {
+            info!(logger, "Unknown timezone: {}", timezone);
+            return Err(MyError::ValueError(None));
+        }
+
+        let stmt = "
+		UPDATE testing.building
+		   SET timezone = $2
+		 WHERE ext_id = $1
+		RETURNING id, ext_id, name, checkin_grace_minutes, timezone;";
+
+        let rows = tx.query(stmt, &[&bldg_ext_id, &timezone]).map_err(|err| {
+            error!(logger, "Failed to update building: DB Error.";
+					"step"=>"set_timezone", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let bldg = match rows.iter().next() {
+            Some(row) => Building { id: row.get(0),
+                                    ext_id: row.get(1),
+                                    name: row.get(2),
+                                    checkin_grace_minutes: row.get(3),
+                                    timezone: row.get(4), },
+            None => {
+                info!(logger, "No such building: {}", bldg_ext_id);
+                return Err(MyError::DBError(DBError::NoRecord));
+            }
+        };
+
+        // touching the time slot recomputes the local times
+        let stmt = "
+		UPDATE testing.meeting mtg
+		   SET time_slot = mtg.time_slot
+		  FROM testing.room r
+		 WHERE mtg.room_id = r.id
+		   AND r.building_id = $1;";
+
+        tx.execute(stmt, &[&bldg.id]).map_err(|err| {
+            error!(logger, "Failed to update meeting local times: DB Error.";
+					"step"=>"set_timezone", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        info!(logger, "Set timezone of building {} to {}", bldg.name, bldg.timezone);
+        Ok(bldg)
+    }
+
+    /// Replace the building's weekly opening hours, given as opening and
+    /// closing local time by day of the week.  Without any, the building is
+    /// open around the clock.
+    pub fn set_opening_hours(bldg_ext_id: Uuid,
+                             hours: Vec<(Weekday, NaiveTime, NaiveTime)>,
+                             logger: &Logger,
+                             tx: &Transaction)
+                             -> Result<OpeningHours, MyError> {
+        let mut weekdays = hours.iter()
+                                .map(|&(day, _, _)| day.num_days_from_monday() as i32)
+                                .collect::<Vec<i32>>();
+        weekdays.sort();
+        weekdays.dedup();
+        if weekdays.len() != hours.len() || hours.iter().any(|&(_, open, close)| open >= close) {
+            info!(logger, "Opening hours need one opening before closing time per weekday");
+            return Err(MyError::ValueError(None));
+        }
+
+        let building_id = Building::get_id(bldg_ext_id, logger, tx)?;
+
+        let stmt = "
+		DELETE FROM testing.building_hours
+		 WHERE building_id = $1;";
+
+        tx.execute(stmt, &[&building_id]).map_err(|err| {
+            error!(logger, "Failed to clear opening hours: DB Error.";
+					"step"=>"set_opening_hours", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let (weekdays, times): (Vec<i32>, Vec<(NaiveTime, NaiveTime)>) =
+            hours.iter()
+                 .map(|&(day, open, close)| (day.num_days_from_monday() as i32, (open, close)))
+                 .unzip();
+        let (open_times, close_times): (Vec<NaiveTime>, Vec<NaiveTime>) = times.into_iter().unzip();
+
+        let stmt = "
+		INSERT INTO testing.building_hours(building_id, weekday, open_time, close_time)
+		SELECT $1, hours.*
+		  FROM unnest($2::integer[], $3::time[], $4::time[]) hours;";
+
+        tx.execute(stmt, &[&building_id, &weekdays, &open_times, &close_times])
+          .map_err(|err| {
+              error!(logger, "Failed to set opening hours: DB Error.";
+					"step"=>"set_opening_hours", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })?;
+
+        info!(logger, "Set opening hours of building {}", bldg_ext_id);
+        Building::get_opening_hours(bldg_ext_id, logger, tx)
+    }
+
+    /// Close the building for the local day, e.g. for a holiday.
+    pub fn add_closure(bldg_ext_id: Uuid,
+                       closed_on: NaiveDate,
+                       description: String,
+                       logger: &Logger,
+                       tx: &Transaction)
+                       -> Result<OpeningHours, MyError> {
+        let building_id = Building::get_id(bldg_ext_id, logger, tx)?;
+
+        let stmt = "
+		INSERT INTO testing.building_closure(building_id, closed_on, description)
+		VALUES ($1, $2, $3)
+		ON CONFLICT (building_id, closed_on) DO UPDATE
+		   SET description = EXCLUDED.description;";
+
+        tx.execute(stmt, &[&building_id, &closed_on, &description])
+          .map_err(|err| {
+              error!(logger, "Failed to add building closure: DB Error.";
+					"step"=>"add_closure", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })?;
+
+        info!(logger, "Closed building {} on {}", bldg_ext_id, closed_on);
+        Building::get_opening_hours(bldg_ext_id, logger, tx)
+    }
+
+    pub fn get_opening_hours(bldg_ext_id: Uuid,
+                             logger: &Logger,
+                             tx: &Transaction)
+                             -> Result<OpeningHours, MyError> {
+        let stmt = "
+		SELECT h.weekday, h.open_time, h.close_time
+		  FROM testing.building_hours h
+		  JOIN testing.building b
+			ON h.building_id = b.id
+		 WHERE b.ext_id = $1
+	  ORDER BY h.weekday;";
+
+        let rows = tx.query(stmt, &[&bldg_ext_id]).map_err(|err| {
+            error!(logger, "Failed to query for opening hours: DB Error.";
+					"step"=>"get_opening_hours", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let hours = rows.iter()
+                        .map(|row| (weekday_from_num(row.get(0)), row.get(1), row.get(2)))
+                        .collect();
+
+        let stmt = "
+		SELECT c.closed_on
+		  FROM testing.building_closure c
+		  JOIN testing.building b
+			ON c.building_id = b.id
+		 WHERE b.ext_id = $1
+	  ORDER BY c.closed_on;";
+
+        let rows = tx.query(stmt, &[&bldg_ext_id]).map_err(|err| {
+            error!(logger, "Failed to query for building closures: DB Error.";
+					"step"=>"get_opening_hours", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let closed_on = rows.iter().map(|row| row.get(0)).collect();
+
+        Ok(OpeningHours { hours, closed_on })
+    }
+
+    /// The times the building is open within a bounded `window`, in order.
+    pub fn get_opening_windows(bldg_ext_id: Uuid,
+                               window: &TSTZRange,
+                               logger: &Logger,
+                               tx: &Transaction)
+                               -> Result<Vec<TSTZRange>, MyError> {
+        let stmt = "
+		SELECT testing.closed_periods(b.id, $2)
+		  FROM testing.building b
+		 WHERE b.ext_id = $1;";
+
+        let rows = tx.query(stmt, &[&bldg_ext_id, window]).map_err(|err| {
+            error!(logger, "Failed to query for closed periods: DB Error.";
+					"step"=>"get_opening_windows", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let closed = rows.iter().map(|row| row.get(0)).collect::<Vec<TSTZRange>>();
+        Ok(timeslots::free_gaps(window, &closed))
+    }
+
+    /// The working hours falling within a bounded `window`, in order.  Like
+    /// opening hours, working hours follow the building's local time.
+    pub fn get_working_windows(bldg_ext_id: Uuid,
+                               hours: &WorkingHours,
+                               window: &TSTZRange,
+                               logger: &Logger,
+                               tx: &Transaction)
+                               -> Result<Vec<TSTZRange>, MyError> {
+        let stmt = "
+		SELECT period
+		  FROM (SELECT tstzrange((d::date + $3::time) AT TIME ZONE b.timezone,
+								 (d::date + $4::time) AT TIME ZONE b.timezone, '[)') * $2::tstzrange AS period
+				  FROM testing.building b,
+					   generate_series((lower($2::tstzrange) AT TIME ZONE b.timezone)::date,
+									   (upper($2::tstzrange) AT TIME ZONE b.timezone)::date,
+									   interval '1 day') d
+				 WHERE b.ext_id = $1
+				   AND extract(isodow FROM d)::integer - 1 = ANY($5::integer[])) periods
+		 WHERE NOT isempty(period)
+	  ORDER BY period;";
+
+        let weekdays = hours.weekdays
+                            .iter()
+                            .map(|day| day.num_days_from_monday() as i32)
+                            .collect::<Vec<i32>>();
+
+        let rows = tx.query(stmt, &[&bldg_ext_id, window, &hours.start, &hours.end, &weekdays])
+                     .map_err(|err| {
+                         error!(logger, "Failed to query for working hours: DB Error.";
+								"step"=>"get_working_windows", "err"=>err.to_string());
+                         MyError::DBError(DBError::PGError(err))
+                     })?;
+
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    fn get_id(bldg_ext_id: Uuid, logger: &Logger, tx: &Transaction) -> Result<i64, MyError> {
+        let stmt = "
+		SELECT id
+		  FROM testing.building
+		 WHERE ext_id = $1;";
+
+        let rows = tx.query(stmt, &[&bldg_ext_id]).map_err(|err| {
+            error!(logger, "Failed to look up building: DB Error.";
+					"step"=>"get_id", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => Ok(row.get(0)),
+            None => {
+                info!(logger, "No such building: {}", bldg_ext_id);
+                Err(MyError::DBError(DBError::NoRecord))
+            }
+        }
+    }
+}
+
+/// A building's weekly opening hours and the days it is closed, in local
+/// time.  A building without opening hours is open around the clock, except
+/// on closure days.
+#[derive(Debug, Clone, Default)]
+pub struct OpeningHours {
+    /// Opening and closing time by day of the week; days not listed are closed
+    pub hours: Vec<(Weekday, NaiveTime, NaiveTime)>,
+    pub closed_on: Vec<NaiveDate>,
 }
 
 #[derive(Debug, Clone)]
@@ -174,24 +799,36 @@ pub struct Room {
     pub building_id: i64,
     pub code: String,
     pub floor_num: i32,
+    pub capacity: i32,
+    /// Time the room is kept free before each meeting
+    pub setup_minutes: i32,
+    /// Time the room is kept free after each meeting
+    pub cleanup_minutes: i32,
+    /// The type whose booking rules apply to the room, if any
+    pub room_type_id: Option<i64>,
 }
 impl Room {
     pub fn add_room(building_id: i64,
                     code: String,
                     floor: i32,
+                    capacity: i32,
                     logger: &Logger,
                     tx: &Transaction)
                     -> Result<Room, MyError> {
         let stmt = "
-		INSERT INTO testing.room(building_id, code, floor_num)
-		VALUES ($1, $2, $3)
+		INSERT INTO testing.room(building_id, code, floor_num, capacity)
+		VALUES ($1, $2, $3, $4)
 		RETURNING testing.room.id,
 				  testing.room.ext_id,
 				  testing.room.building_id,
 				  testing.room.code,
-				  testing.room.floor_num;";
+				  testing.room.floor_num,
+				  testing.room.capacity,
+				  testing.room.setup_minutes,
+				  testing.room.cleanup_minutes,
+				  testing.room.room_type_id;";
 
-        tx.query(stmt, &[&building_id, &code, &floor])
+        tx.query(stmt, &[&building_id, &code, &floor, &capacity])
           .map_err(|err| {
               error!(&logger, "Failed to add meeting room: DB Error.";
 					"step"=>"add_room", "err"=>err.to_string());
@@ -205,7 +842,11 @@ impl Room {
                                         ext_id: row.get(1),
                                         building_id: row.get(2),
                                         code: row.get(3),
-                                        floor_num: row.get(4), };
+                                        floor_num: row.get(4),
+                                        capacity: row.get(5),
+                                        setup_minutes: row.get(6),
+                                        cleanup_minutes: row.get(7),
+                                        room_type_id: row.get(8), };
                     info!(&logger, "Added meeting room: {}", room.code);
                     room })
                   .ok_or_else(|| {
@@ -219,7 +860,8 @@ impl Room {
     pub fn get_rooms(logger: &Logger, tx: &Transaction)
                         -> Result<Vec<Room>, MyError> {
         let stmt = "
-		SELECT id, ext_id, building_id, code, floor_num 
+		SELECT id, ext_id, building_id, code, floor_num, capacity,
+			   setup_minutes, cleanup_minutes, room_type_id
 		  FROM testing.room;";
 
         tx.query(stmt, &[])
@@ -240,147 +882,3274 @@ impl Room {
                                                      ext_id: row.get(1),
                                                      building_id: row.get(2),
                                                      code: row.get(3),
-                                                     floor_num: row.get(4), })
+                                                     floor_num: row.get(4),
+                                                     capacity: row.get(5),
+                                                     setup_minutes: row.get(6),
+                                                     cleanup_minutes: row.get(7),
+                                                     room_type_id: row.get(8), })
                               .collect::<Vec<Room>>();
               Ok(rooms)
           })
     }
-}
-
-#[derive(Debug)]
-pub struct Meeting {
-    pub id: i64,
-    pub ext_id: Uuid,
-    pub organizer_id: i64,
-    pub room_id: i64,
-    pub title: String,
-    pub time_slot: TSTZRange,
-}
-impl Meeting {
-    pub fn schedule_meeting(username: String,
-                            bldg_ext_id: Uuid,
-                            room_code: String,
-                            start_dt: String,
-                            end_dt: String,
-                            title: String,
-                            logger: &Logger,
-                            tx: &Transaction)
-                            -> Result<Meeting, MyError> {
-        let start_dt = match start_dt.parse::<DateTime<Utc>>() {
-            Ok(x) => x,
-            Err(_) => {
-                info!(logger, "Failed to convert user-provided start_dt");
-                return Err(MyError::ValueError);
-            }
-        };
-
-        let end_dt = match end_dt.parse::<DateTime<Utc>>() {
-            Ok(x) => x,
-            Err(_) => {
-                info!(logger, "Failed to convert user-provided end_dt");
-                return Err(MyError::ValueError);
-            }
-        };
-        let time_slot: TSTZRange = range!('[' start_dt, end_dt; ']');
-
-        let stmt = "
-		INSERT INTO testing.meeting(organizer_id, room_id, title, time_slot)
-		SELECT u.id, rooms.id, $1, $2
-		FROM (SELECT r.id
-			    FROM testing.room r
-				JOIN testing.building b
-				  ON r.building_id = b.id
-			   WHERE r.code = $3
-			     AND b.ext_id = $4) rooms, testing.users u
-		WHERE u.username = $5
-		RETURNING  testing.meeting.id,
-					testing.meeting.ext_id,
-					testing.meeting.organizer_id,
-					testing.meeting.room_id,
-					testing.meeting.title,
-					testing.meeting.time_slot;";
+    /// Rooms in the building that are free for the entire time slot, best
+    /// candidates first.  There are none while the building is closed, and
+    /// blacked out rooms are never free.
+    ///
+    /// Candidates are ranked by distance from the preferred floor, if any, and
+    /// then by how closely their capacity fits the required one.  Requiring an
+    /// amenity that is not in the catalog matches no rooms.
+    pub fn find_available(bldg_ext_id: Uuid,
+                          time_slot: TSTZRange,
+                          filters: &RoomFilter,
+                          logger: &Logger,
+                          tx: &Transaction)
+                          -> Result<Vec<Room>, MyError> {
+        let stmt = "
+		SELECT r.id, r.ext_id, r.building_id, r.code, r.floor_num, r.capacity,
+			   r.setup_minutes, r.cleanup_minutes, r.room_type_id
+		  FROM testing.room r
+		  JOIN testing.building b
+			ON r.building_id = b.id
+		 WHERE b.ext_id = $1
+		   AND r.capacity >= COALESCE($3, 0)
+		   AND testing.within_opening_hours(b.id, $2)
+		   AND NOT testing.room_blacked_out(r.id, testing.buffered_slot(r.id, $2))
+		   AND NOT EXISTS (SELECT true
+							 FROM testing.meeting mtg
+							WHERE mtg.room_id = r.id
+							  AND mtg.blocked_slot && testing.buffered_slot(r.id, $2))
+		   AND NOT EXISTS (SELECT true
+							 FROM unnest($5::varchar[]) required(code)
+							WHERE NOT EXISTS (SELECT true
+												FROM testing.room_amenity ra
+												JOIN testing.amenity a
+												  ON ra.amenity_id = a.id
+											   WHERE ra.room_id = r.id
+												 AND a.code = required.code))
+	  ORDER BY abs(r.floor_num - COALESCE($4, r.floor_num)),
+			   r.capacity,
+			   r.code;";
 
         tx.query(stmt,
-                 &[&title, &time_slot, &room_code, &bldg_ext_id, &username])
+                 &[&bldg_ext_id,
+                   &time_slot,
+                   &filters.min_capacity,
+                   &filters.preferred_floor,
+                   &filters.amenities])
           .map_err(|err| {
-              if Some(&EXCLUSION_VIOLATION) == err.code() {
-                  info!(logger, "Meeting schedule overlap.  Could not schedule.");
-                  return MyError::MeetingError(MeetingError::ScheduleConflict);
-              } else {
-                  error!(logger, "Failed to schedule meeting: Unknown DB Error.";
-						"step"=>"schedule_meeting", "err"=>err.to_string());
-                  MyError::DBError(DBError::PGError(err))
-              }
-          })
-          .and_then(|rows: Rows| {
-              rows.into_iter()
-                  .next()
-                  .map(|row: Row| {
-                           let mtg = Meeting { id: row.get(0),
-                                               ext_id: row.get(1),
-                                               organizer_id: row.get(2),
-                                               room_id: row.get(3),
-                                               title: row.get(4),
-                                               time_slot: row.get(5), };
-                           info!(logger, "Scheduled Meeting: {}", mtg.ext_id);
-                           mtg
-                       })
-                  .ok_or_else(|| {
-                      error!(logger, "Error scheduling meeting in db: No record returned.";
-  							  "step"=>"schedule_meeting");
-                      MyError::DBError(DBError::NoRecord)
-                  })
+              error!(logger, "Failed to query for available rooms: DB Error.";
+					"step"=>"find_available", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
           })
+          .map(|rows: Rows| rows.iter().map(|row| Room::from_row(&row)).collect())
     }
 
-    /// Confirm what preferred timeslots are available for scheduling.
+    pub fn get_room(bldg_ext_id: Uuid,
+                    room_code: String,
+                    logger: &Logger,
+                    tx: &Transaction)
+                    -> Result<Room, MyError> {
+        let stmt = "
+		SELECT r.id, r.ext_id, r.building_id, r.code, r.floor_num, r.capacity,
+			   r.setup_minutes, r.cleanup_minutes, r.room_type_id
+		  FROM testing.room r
+		  JOIN testing.building b
+			ON r.building_id = b.id
+		 WHERE r.code = $1
+		   AND b.ext_id = $2;";
+
+        let rows = tx.query(stmt, &[&room_code, &bldg_ext_id]).map_err(|err| {
+            error!(logger, "Failed to query for room: DB Error.";
+					"step"=>"get_room", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => Ok(Room::from_row(&row)),
+            None => {
+                info!(logger, "No room {} in building {}", room_code, bldg_ext_id);
+                Err(MyError::DBError(DBError::NoRecord))
+            }
+        }
+    }
+
+    /// Set the time the room is kept free before and after each meeting.
     ///
-    /// The point of this function is to show how to import datasets for use
-    /// within SQL.
-    pub fn check_room_availability_v1(room_cd: String,
-                                      bldg_ext_id: Uuid,
-                                      preferred_timeslots: Vec<(i64, String, String)>,
-                                      logger: &Logger,
-                                      tx: &Transaction)
-                                      -> Result<Vec<i64>, MyError> {
-        let (p_ids, p_timeslots): (Vec<i64>, Vec<TSTZRange>) =
-			preferred_timeslots
-			.into_iter()
-			.map(|(id, start, end)| {
-				let start_dt = start.parse::<DateTime<Utc>>().unwrap(); //unsafe
-				let end_dt = end.parse::<DateTime<Utc>>().unwrap(); //unsafe
-        		let time_slot: TSTZRange = range!('[' start_dt, end_dt; ']');
-
-				(id, time_slot)
-			})
-			.unzip();
-
-        let stmt = "
-		SELECT pref.id 
-		  FROM unnest($1::bigint[], $2::tstzrange[]) as pref(id, time_slot)
-		 WHERE not exists (SELECT true
-		 					 FROM testing.meeting mtg
-							 JOIN testing.room r
-							   ON mtg.room_id = r.id
-							 JOIN testing.building b
-							   ON r.building_id = b.id
-							WHERE r.code = $3
-							  AND b.ext_id = $4
-							  AND mtg.time_slot && pref.time_slot);";
-
-        tx.query(stmt, &[&p_ids, &p_timeslots, &room_cd, &bldg_ext_id])
-          .map_err(|err| {
-              error!(logger, "Failed to check room availability: DB Error.";
-					"step"=>"check_room_availability", "err"=>err.to_string());
-              MyError::DBError(DBError::PGError(err))
-          })
-          .and_then(|rows: Rows| {
-            let mtgs = rows.into_iter()
-                            .map(|row: Row| {
-                                    let id: i64 = row.get(0);
-                                    id
-                                })
-                            .collect::<Vec<i64>>();
-            Ok(mtgs)})
+    /// The buffers also apply to meetings already booked that have not ended
+    /// yet, so the change fails with a `ScheduleConflict` if any of those
+    /// would no longer fit.
+    pub fn set_buffers(bldg_ext_id: Uuid,
+                       room_code: String,
+                       setup: Duration,
+                       cleanup: Duration,
+                       logger: &Logger,
+                       tx: &Transaction)
+                       -> Result<Room, MyError> {
+        if setup < Duration::zero() || cleanup < Duration::zero() {
+            info!(logger, "Room buffers may not be negative");
+            return Err(MyError::ValueError(None));
+        }
+        let setup_minutes = setup.num_minutes() as i32;
+        let cleanup_minutes = cleanup.num_minutes() as i32;
+
+        let room = Room::get_room(bldg_ext_id, room_code, logger, tx)?;
+
+        let sp = tx.savepoint("set_buffers").map_err(|err| {
+            error!(logger, "Failed to create savepoint";
+					"step"=>"set_buffers", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let stmt = "
+		UPDATE testing.room
+		   SET setup_minutes = $2,
+			   cleanup_minutes = $3
+		 WHERE id = $1
+		RETURNING testing.room.*;";
+
+        let rows = sp.query(stmt, &[&room.id, &setup_minutes, &cleanup_minutes])
+                     .map_err(|err| {
+                         error!(logger, "Failed to update room: DB Error.";
+								"step"=>"set_buffers", "err"=>err.to_string());
+                         MyError::DBError(DBError::PGError(err))
+                     })?;
+
+        let room = match rows.iter().next() {
+            Some(row) => Room::from_row(&row),
+            None => {
+                error!(logger, "Error updating room in db: No record returned.";
+						"step"=>"set_buffers");
+                return Err(MyError::DBError(DBError::NoRecord));
+            }
+        };
+
+        // touching the time slot recomputes the blocked slot
+        let stmt = "
+		UPDATE testing.meeting
+		   SET time_slot = time_slot
+		 WHERE room_id = $1
+		   AND upper(time_slot) > clock_timestamp();";
+
+        sp.execute(stmt, &[&room.id])
+          .map_err(|err| map_booking_err(err, "set_buffers", logger))?;
+
+        sp.commit().map_err(|err| {
+            error!(logger, "Failed to release savepoint";
+					"step"=>"set_buffers", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        info!(logger, "Set buffers of room {}", room.code;
+              "setup"=>setup_minutes, "cleanup"=>cleanup_minutes);
+        Ok(room)
+    }
+
+    /// Set the type of the room, or clear it with None, changing the rules
+    /// that apply to new bookings.
+    pub fn set_room_type(bldg_ext_id: Uuid,
+                         room_code: String,
+                         type_code: Option<String>,
+                         logger: &Logger,
+                         tx: &Transaction)
+                         -> Result<Room, MyError> {
+        let room = Room::get_room(bldg_ext_id, room_code, logger, tx)?;
+
+        let stmt = "
+		UPDATE testing.room r
+		   SET room_type_id = t.id
+		  FROM (SELECT $2::varchar AS code) requested
+	 LEFT JOIN testing.room_type t
+			ON t.code = requested.code
+		 WHERE r.id = $1
+		   AND (requested.code IS NULL OR t.id IS NOT NULL)
+		RETURNING r.*;";
+
+        let rows = tx.query(stmt, &[&room.id, &type_code]).map_err(|err| {
+            error!(logger, "Failed to update room: DB Error.";
+					"step"=>"set_room_type", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => {
+                let room = Room::from_row(&row);
+                info!(logger, "Set type of room {}", room.code; "type"=>format!("{:?}", type_code));
+                Ok(room)
+            }
+            None => {
+                info!(logger, "No such room type: {:?}", type_code);
+                Err(MyError::DBError(DBError::NoRecord))
+            }
+        }
+    }
+
+    /// The room's amenities, by code.
+    pub fn get_amenities(bldg_ext_id: Uuid,
+                         room_code: String,
+                         logger: &Logger,
+                         tx: &Transaction)
+                         -> Result<Vec<Amenity>, MyError> {
+        let room = Room::get_room(bldg_ext_id, room_code, logger, tx)?;
+        Room::amenities(room.id, logger, tx)
+    }
+
+    /// Equip the room with an amenity from the catalog.  Adding an amenity the
+    /// room already has no effect.  Returns all of the room's amenities.
+    pub fn add_amenity(bldg_ext_id: Uuid,
+                       room_code: String,
+                       amenity_code: String,
+                       logger: &Logger,
+                       tx: &Transaction)
+                       -> Result<Vec<Amenity>, MyError> {
+        let room = Room::get_room(bldg_ext_id, room_code, logger, tx)?;
+
+        // the no-op update returns an existing mapping, so that only an
+        // unknown amenity returns nothing
+        let stmt = "
+		INSERT INTO testing.room_amenity(room_id, amenity_id)
+		SELECT $1, a.id
+		  FROM testing.amenity a
+		 WHERE a.code = $2
+		ON CONFLICT (room_id, amenity_id) DO UPDATE
+		   SET room_id = EXCLUDED.room_id
+		RETURNING id;";
+
+        let rows = tx.query(stmt, &[&room.id, &amenity_code]).map_err(|err| {
+            error!(logger, "Failed to add room amenity: DB Error.";
+					"step"=>"add_amenity", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        if rows.is_empty() {
+            info!(logger, "No such amenity: {}", amenity_code);
+            return Err(MyError::DBError(DBError::NoRecord));
+        }
+
+        info!(logger, "Added amenity {} to room {}", amenity_code, room.code);
+        Room::amenities(room.id, logger, tx)
+    }
+
+    /// Take an amenity away from the room.  Returns the room's remaining
+    /// amenities.
+    pub fn remove_amenity(bldg_ext_id: Uuid,
+                          room_code: String,
+                          amenity_code: String,
+                          logger: &Logger,
+                          tx: &Transaction)
+                          -> Result<Vec<Amenity>, MyError> {
+        let room = Room::get_room(bldg_ext_id, room_code, logger, tx)?;
+
+        let stmt = "
+		DELETE FROM testing.room_amenity ra
+		 USING testing.amenity a
+		 WHERE ra.amenity_id = a.id
+		   AND ra.room_id = $1
+		   AND a.code = $2;";
+
+        let removed = tx.execute(stmt, &[&room.id, &amenity_code]).map_err(|err| {
+            error!(logger, "Failed to remove room amenity: DB Error.";
+					"step"=>"remove_amenity", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        if removed == 0 {
+            info!(logger, "Room {} does not have amenity {}", room.code, amenity_code);
+            return Err(MyError::DBError(DBError::NoRecord));
+        }
+
+        info!(logger, "Removed amenity {} from room {}", amenity_code, room.code);
+        Room::amenities(room.id, logger, tx)
+    }
+
+    fn amenities(room_id: i64, logger: &Logger, tx: &Transaction) -> Result<Vec<Amenity>, MyError> {
+        let stmt = "
+		SELECT a.id, a.ext_id, a.code, a.name
+		  FROM testing.amenity a
+		  JOIN testing.room_amenity ra
+			ON ra.amenity_id = a.id
+		 WHERE ra.room_id = $1
+	  ORDER BY a.code;";
+
+        tx.query(stmt, &[&room_id])
+          .map_err(|err| {
+              error!(logger, "Failed to query for room amenities: DB Error.";
+					"step"=>"get_amenities", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })
+          .map(|rows: Rows| rows.iter().map(|row| Amenity::from_row(&row)).collect())
+    }
+
+    /// Fail with `MissingAmenities` unless the room has all of the amenities,
+    /// given by code.
+    fn require_amenities(room_id: i64,
+                         amenities: &[String],
+                         logger: &Logger,
+                         tx: &Transaction)
+                         -> Result<(), MyError> {
+        if amenities.is_empty() {
+            return Ok(());
+        }
+
+        let present = Room::amenities(room_id, logger, tx)?;
+        let missing = amenities.iter()
+                               .filter(|code| !present.iter().any(|a| &a.code == *code))
+                               .cloned()
+                               .collect::<Vec<String>>();
+        if !missing.is_empty() {
+            info!(logger, "Room {} lacks amenities: {:?}", room_id, missing);
+            return Err(MyError::MeetingError(MeetingError::MissingAmenities { missing }));
+        }
+
+        Ok(())
+    }
+
+    /// The room's busy and free time within `window`, e.g. to render a day.
+    /// The free time excludes the room's setup and cleanup buffers around
+    /// each meeting, its blackouts, and the times the building is closed.
+    pub fn get_free_busy(bldg_ext_id: Uuid,
+                         room_code: String,
+                         window: TSTZRange,
+                         logger: &Logger,
+                         tx: &Transaction)
+                         -> Result<FreeBusy, MyError> {
+        let room = Room::get_room(bldg_ext_id, room_code, logger, tx)?;
+
+        let stmt = "
+		SELECT mtg.ext_id, mtg.time_slot * $2, mtg.blocked_slot
+		  FROM testing.meeting mtg
+		 WHERE mtg.room_id = $1
+		   AND mtg.blocked_slot && $2
+	  ORDER BY lower(mtg.time_slot);";
+
+        let rows = tx.query(stmt, &[&room.id, &window]).map_err(|err| {
+            error!(logger, "Failed to query for room schedule: DB Error.";
+					"step"=>"get_free_busy", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        // a meeting just outside the window may still block part of it
+        let busy = rows.iter()
+                       .map(|row| BusyBlock { meeting_ext_id: row.get(0),
+                                              time_slot: row.get(1), })
+                       .filter(|block| !block.time_slot.is_empty())
+                       .collect::<Vec<BusyBlock>>();
+
+        let mut blocked_slots = rows.iter().map(|row| row.get(2)).collect::<Vec<TSTZRange>>();
+
+        let stmt = "
+		SELECT bo.id, bo.ext_id, bo.room_id, bo.reason, bo.time_slot * $2 AS time_slot
+		  FROM testing.room_blackout bo
+		 WHERE bo.room_id = $1
+		   AND bo.time_slot && $2
+	  ORDER BY lower(bo.time_slot);";
+
+        let blackouts = tx.query(stmt, &[&room.id, &window])
+                          .map_err(|err| {
+                              error!(logger, "Failed to query for room blackouts: DB Error.";
+									"step"=>"get_free_busy", "err"=>err.to_string());
+                              MyError::DBError(DBError::PGError(err))
+                          })?
+                          .iter()
+                          .map(|row| Blackout::from_row(&row))
+                          .collect::<Vec<Blackout>>();
+        blocked_slots.extend(blackouts.iter().map(|blackout| blackout.time_slot));
+
+        let opening_windows = Building::get_opening_windows(bldg_ext_id, &window, logger, tx)?;
+        let free = timeslots::mask(&timeslots::free_gaps(&window, &blocked_slots),
+                                   &opening_windows);
+
+        Ok(FreeBusy { busy,
+                      blackouts,
+                      free })
+    }
+
+    /// Block the room from being booked during the time slot, e.g. for
+    /// repairs.  Fails with a `ScheduleConflict` if meetings are booked or
+    /// another blackout is set during that time.
+    pub fn add_blackout(bldg_ext_id: Uuid,
+                        room_code: String,
+                        start_dt: String,
+                        end_dt: String,
+                        reason: String,
+                        logger: &Logger,
+                        tx: &Transaction)
+                        -> Result<Blackout, MyError> {
+        let time_slot = parse_time_slot(&start_dt, &end_dt, bldg_ext_id, logger, tx)?;
+        let room = Room::get_room(bldg_ext_id, room_code, logger, tx)?;
+
+        let sp = tx.savepoint("add_blackout").map_err(|err| {
+            error!(logger, "Failed to create savepoint";
+					"step"=>"add_blackout", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let stmt = "
+		INSERT INTO testing.room_blackout(room_id, reason, time_slot)
+		VALUES ($1, $2, $3)
+		RETURNING id, ext_id, room_id, reason, time_slot;";
+
+        let rows = sp.query(stmt, &[&room.id, &reason, &time_slot])
+                     .map_err(|err| map_booking_err(err, "add_blackout", logger))?;
+
+        let blackout = match rows.iter().next() {
+            Some(row) => Blackout::from_row(&row),
+            None => {
+                error!(logger, "Error adding blackout to db: No record returned.";
+						"step"=>"add_blackout");
+                return Err(MyError::DBError(DBError::NoRecord));
+            }
+        };
+
+        sp.commit().map_err(|err| {
+            error!(logger, "Failed to release savepoint";
+					"step"=>"add_blackout", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        info!(logger, "Blacked out room {}: {}", room.code, blackout.ext_id; "reason"=>&reason);
+        Ok(blackout)
+    }
+
+    /// Lift a blackout, making the time available to the room's waitlist.
+    pub fn remove_blackout(ext_id: Uuid,
+                           logger: &Logger,
+                           tx: &Transaction)
+                           -> Result<Blackout, MyError> {
+        let stmt = "
+		DELETE FROM testing.room_blackout
+		 WHERE ext_id = $1
+		RETURNING id, ext_id, room_id, reason, time_slot;";
+
+        let rows = tx.query(stmt, &[&ext_id]).map_err(|err| {
+            error!(logger, "Failed to remove blackout: DB Error.";
+					"step"=>"remove_blackout", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let blackout = match rows.iter().next() {
+            Some(row) => Blackout::from_row(&row),
+            None => {
+                info!(logger, "No such blackout: {}", ext_id);
+                return Err(MyError::DBError(DBError::NoRecord));
+            }
+        };
+        info!(logger, "Removed blackout: {}", blackout.ext_id);
+
+        WaitlistEntry::promote(blackout.room_id, logger, tx)?;
+        Ok(blackout)
+    }
+
+    fn from_row(row: &Row) -> Room {
+        Room { id: row.get("id"),
+               ext_id: row.get("ext_id"),
+               building_id: row.get("building_id"),
+               code: row.get("code"),
+               floor_num: row.get("floor_num"),
+               capacity: row.get("capacity"),
+               setup_minutes: row.get("setup_minutes"),
+               cleanup_minutes: row.get("cleanup_minutes"),
+               room_type_id: row.get("room_type_id"), }
+    }
+}
+
+/// A time slot during which a room is booked.
+#[derive(Debug)]
+pub struct BusyBlock {
+    pub meeting_ext_id: Uuid,
+    pub time_slot: TSTZRange,
+}
+
+/// A time during which a room cannot be booked at all.
+#[derive(Debug)]
+pub struct Blackout {
+    pub id: i64,
+    pub ext_id: Uuid,
+    pub room_id: i64,
+    pub reason: String,
+    pub time_slot: TSTZRange,
+}
+impl Blackout {
+    fn from_row(row: &Row) -> Blackout {
+        Blackout { id: row.get("id"),
+                   ext_id: row.get("ext_id"),
+                   room_id: row.get("room_id"),
+                   reason: row.get("reason"),
+                   time_slot: row.get("time_slot"), }
+    }
+}
+
+/// A room's schedule within a window: busy blocks and blackouts, clipped to
+/// the window, and the free gaps between them.
+#[derive(Debug)]
+pub struct FreeBusy {
+    pub busy: Vec<BusyBlock>,
+    pub blackouts: Vec<Blackout>,
+    pub free: Vec<TSTZRange>,
+}
+
+/// Criteria for `Room::find_available`.
+#[derive(Debug, Clone, Default)]
+pub struct RoomFilter {
+    /// Only rooms seating at least this many people
+    pub min_capacity: Option<i32>,
+    /// Prefer rooms on, or closest to, this floor
+    pub preferred_floor: Option<i32>,
+    /// Only rooms with all of these amenities, by code
+    pub amenities: Vec<String>,
+}
+
+/// Something a room may be equipped with, such as a projector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Amenity {
+    pub id: i64,
+    pub ext_id: Uuid,
+    /// Short identifier, e.g. "projector"
+    pub code: String,
+    pub name: String,
+}
+impl Amenity {
+    /// Add an amenity to the catalog, for rooms to be equipped with.
+    pub fn add_amenity(code: String,
+                       name: String,
+                       logger: &Logger,
+                       tx: &Transaction)
+                       -> Result<Amenity, MyError> {
+        let stmt = "
+		INSERT INTO testing.amenity(code, name)
+		VALUES ($1, $2)
+		RETURNING id, ext_id, code, name;";
+
+        let rows = tx.query(stmt, &[&code, &name]).map_err(|err| {
+            error!(logger, "Failed to add amenity: DB Error.";
+					"step"=>"add_amenity", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => {
+                let amenity = Amenity::from_row(&row);
+                info!(logger, "Added amenity: {}", amenity.code);
+                Ok(amenity)
+            }
+            None => {
+                error!(logger, "Error adding amenity to db: No record returned.";
+						"step"=>"add_amenity");
+                Err(MyError::DBError(DBError::NoRecord))
+            }
+        }
+    }
+
+    /// The whole catalog, by code.
+    pub fn get_amenities(logger: &Logger, tx: &Transaction) -> Result<Vec<Amenity>, MyError> {
+        let stmt = "
+		SELECT id, ext_id, code, name
+		  FROM testing.amenity
+	  ORDER BY code;";
+
+        tx.query(stmt, &[])
+          .map_err(|err| {
+              error!(logger, "Failed to query for amenities: DB Error.";
+					"step"=>"get_amenities", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })
+          .map(|rows: Rows| rows.iter().map(|row| Amenity::from_row(&row)).collect())
+    }
+
+    /// Rename an amenity, keeping the rooms equipped with it.
+    pub fn rename_amenity(code: String,
+                          name: String,
+                          logger: &Logger,
+                          tx: &Transaction)
+                          -> Result<Amenity, MyError> {
+        let stmt = "
+		UPDATE testing.amenity
+		   SET name = $2
+		 WHERE code = $1
+		RETURNING id, ext_id, code, name;";
+
+        let rows = tx.query(stmt, &[&code, &name]).map_err(|err| {
+            error!(logger, "Failed to rename amenity: DB Error.";
+					"step"=>"rename_amenity", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => Ok(Amenity::from_row(&row)),
+            None => {
+                info!(logger, "No such amenity: {}", code);
+                Err(MyError::DBError(DBError::NoRecord))
+            }
+        }
+    }
+
+    /// Remove an amenity from the catalog and from every room equipped with it.
+    pub fn remove_amenity(code: String,
+                          logger: &Logger,
+                          tx: &Transaction)
+                          -> Result<Amenity, MyError> {
+        let stmt = "
+		DELETE FROM testing.amenity
+		 WHERE code = $1
+		RETURNING id, ext_id, code, name;";
+
+        let rows = tx.query(stmt, &[&code]).map_err(|err| {
+            error!(logger, "Failed to remove amenity: DB Error.";
+					"step"=>"remove_amenity", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => {
+                let amenity = Amenity::from_row(&row);
+                info!(logger, "Removed amenity: {}", amenity.code);
+                Ok(amenity)
+            }
+            None => {
+                info!(logger, "No such amenity: {}", code);
+                Err(MyError::DBError(DBError::NoRecord))
+            }
+        }
+    }
+
+    fn from_row(row: &Row) -> Amenity {
+        Amenity { id: row.get("id"),
+                  ext_id: row.get("ext_id"),
+                  code: row.get("code"),
+                  name: row.get("name"), }
+    }
+}
+
+/// Rules for booking a room, set per room type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BookingRules {
+    /// Longest a single booking may last, unlimited if None
+    pub max_booking_minutes: Option<i32>,
+    /// How many days ahead a booking may start, unlimited if None
+    pub advance_booking_days: Option<i32>,
+    /// Whether bookings are pending until approved by an administrator
+    pub requires_approval: bool,
+}
+
+/// A kind of room, such as a phone booth or an auditorium.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoomType {
+    pub id: i64,
+    pub ext_id: Uuid,
+    /// Short identifier, e.g. "booth"
+    pub code: String,
+    pub name: String,
+    pub rules: BookingRules,
+}
+impl RoomType {
+    pub fn add_room_type(code: String,
+                         name: String,
+                         rules: &BookingRules,
+                         logger: &Logger,
+                         tx: &Transaction)
+                         -> Result<RoomType, MyError> {
+        let stmt = "
+		INSERT INTO testing.room_type(code, name, max_booking_minutes,
+									  advance_booking_days, requires_approval)
+		VALUES ($1, $2, $3, $4, $5)
+		RETURNING testing.room_type.*;";
+
+        let rows = tx.query(stmt,
+                            &[&code,
+                              &name,
+                              &rules.max_booking_minutes,
+                              &rules.advance_booking_days,
+                              &rules.requires_approval])
+                     .map_err(|err| {
+                         error!(logger, "Failed to add room type: DB Error.";
+								"step"=>"add_room_type", "err"=>err.to_string());
+                         MyError::DBError(DBError::PGError(err))
+                     })?;
+
+        match rows.iter().next() {
+            Some(row) => {
+                let room_type = RoomType::from_row(&row);
+                info!(logger, "Added room type: {}", room_type.code);
+                Ok(room_type)
+            }
+            None => {
+                error!(logger, "Error adding room type to db: No record returned.";
+						"step"=>"add_room_type");
+                Err(MyError::DBError(DBError::NoRecord))
+            }
+        }
+    }
+
+    pub fn get_room_types(logger: &Logger, tx: &Transaction) -> Result<Vec<RoomType>, MyError> {
+        let stmt = "
+		SELECT *
+		  FROM testing.room_type
+	  ORDER BY code;";
+
+        tx.query(stmt, &[])
+          .map_err(|err| {
+              error!(logger, "Failed to query for room types: DB Error.";
+					"step"=>"get_room_types", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })
+          .map(|rows: Rows| rows.iter().map(|row| RoomType::from_row(&row)).collect())
+    }
+
+    /// Change the booking rules of the type.  Meetings already booked are not
+    /// affected.
+    pub fn set_rules(code: String,
+                     rules: &BookingRules,
+                     logger: &Logger,
+                     tx: &Transaction)
+                     -> Result<RoomType, MyError> {
+        let stmt = "
+		UPDATE testing.room_type
+		   SET max_booking_minutes = $2,
+			   advance_booking_days = $3,
+			   requires_approval = $4
+		 WHERE code = $1
+		RETURNING testing.room_type.*;";
+
+        let rows = tx.query(stmt,
+                            &[&code,
+                              &rules.max_booking_minutes,
+                              &rules.advance_booking_days,
+                              &rules.requires_approval])
+                     .map_err(|err| {
+                         error!(logger, "Failed to update room type: DB Error.";
+								"step"=>"set_rules", "err"=>err.to_string());
+                         MyError::DBError(DBError::PGError(err))
+                     })?;
+
+        match rows.iter().next() {
+            Some(row) => Ok(RoomType::from_row(&row)),
+            None => {
+                info!(logger, "No such room type: {}", code);
+                Err(MyError::DBError(DBError::NoRecord))
+            }
+        }
+    }
+
+    fn from_row(row: &Row) -> RoomType {
+        RoomType { id: row.get("id"),
+                   ext_id: row.get("ext_id"),
+                   code: row.get("code"),
+                   name: row.get("name"),
+                   rules: BookingRules { max_booking_minutes: row.get("max_booking_minutes"),
+                                         advance_booking_days: row.get("advance_booking_days"),
+                                         requires_approval: row.get("requires_approval"), }, }
+    }
+}
+
+#[derive(Debug)]
+pub struct Meeting {
+    pub id: i64,
+    pub ext_id: Uuid,
+    pub organizer_id: i64,
+    pub room_id: i64,
+    pub title: String,
+    pub time_slot: TSTZRange,
+    pub series_id: Option<i64>,
+    pub occurrence_start: Option<DateTime<Utc>>,
+    pub is_modified: bool,
+    pub headcount: Option<i32>,
+    pub status: MeetingStatus,
+    pub hold_expires_at: Option<DateTime<Utc>>,
+    pub priority: Priority,
+    pub checked_in_at: Option<DateTime<Utc>>,
+    /// When the room was released for lack of a check-in
+    pub released_at: Option<DateTime<Utc>>,
+    /// Start of the time slot in the building's local time
+    pub local_start: DateTime<FixedOffset>,
+    /// End of the time slot in the building's local time
+    pub local_end: DateTime<FixedOffset>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MeetingStatus {
+    /// Tentatively booked until `Meeting::hold_expires_at`
+    Hold,
+    /// Awaiting approval, as required by the room's type
+    Pending,
+    Confirmed,
+}
+impl MeetingStatus {
+    pub fn code(&self) -> &'static str {
+        match *self {
+            MeetingStatus::Hold => "hold",
+            MeetingStatus::Pending => "pending",
+            MeetingStatus::Confirmed => "confirmed",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<MeetingStatus> {
+        match code {
+            "hold" => Some(MeetingStatus::Hold),
+            "pending" => Some(MeetingStatus::Pending),
+            "confirmed" => Some(MeetingStatus::Confirmed),
+            _ => None,
+        }
+    }
+}
+
+/// How important a meeting is.  Scheduling with `Meeting::schedule_bumping`
+/// displaces meetings of a lower priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Priority {
+    #[default]
+    Routine,
+    High,
+    /// e.g. executive or all-hands meetings
+    Critical,
+}
+impl Priority {
+    pub fn level(&self) -> i16 {
+        match *self {
+            Priority::Routine => 0,
+            Priority::High => 1,
+            Priority::Critical => 2,
+        }
+    }
+
+    pub fn from_level(level: i16) -> Option<Priority> {
+        match level {
+            0 => Some(Priority::Routine),
+            1 => Some(Priority::High),
+            2 => Some(Priority::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// How a meeting relates to the series it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OccurrenceKind {
+    /// Booked as generated by the series' recurrence rule
+    Generated,
+    /// Moved to another time or room after the series was booked
+    Modified,
+}
+
+/// How to treat a participant being booked in another meeting at the same time.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ConflictPolicy {
+    /// Refuse the booking with `MeetingError::AttendeeConflict`
+    #[default]
+    Error,
+    /// Log a warning and proceed with the booking
+    Warn,
+}
+impl ConflictPolicy {
+    pub fn code(&self) -> &'static str {
+        match *self {
+            ConflictPolicy::Error => "error",
+            ConflictPolicy::Warn => "warn",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<ConflictPolicy> {
+        match code {
+            "error" => Some(ConflictPolicy::Error),
+            "warn" => Some(ConflictPolicy::Warn),
+            _ => None,
+        }
+    }
+}
+
+/// Optional settings for scheduling a meeting.
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleOptions {
+    pub conflict_policy: ConflictPolicy,
+    /// Expected number of people, checked against the room's capacity
+    pub headcount: Option<i32>,
+    /// Only administrators may book above `Priority::Routine`
+    pub priority: Priority,
+}
+
+/// A meeting cancelled to make room for one of a higher priority.
+#[derive(Debug)]
+pub struct BumpedMeeting {
+    pub meeting: Meeting,
+    /// Username of the organizer, who is to be told about it
+    pub organizer: String,
+}
+
+/// One of the meetings to book with `Meeting::schedule_many`, with the same
+/// arguments as `Meeting::schedule_meeting`.
+#[derive(Debug, Clone)]
+pub struct BookingRequest {
+    pub username: String,
+    pub bldg_ext_id: Uuid,
+    pub room_code: String,
+    pub start_dt: String,
+    pub end_dt: String,
+    pub title: String,
+    pub options: ScheduleOptions,
+}
+
+/// A participant of a meeting who is also committed to another meeting at an
+/// overlapping time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersonConflict {
+    pub user: Uuid,
+    pub conflicting_meeting: Uuid,
+}
+
+/// Criteria for `Meeting::list`.  Criteria left unset match any meeting.
+#[derive(Debug, Clone, Default)]
+pub struct MeetingFilter {
+    /// Username of the organizer
+    pub organizer: Option<String>,
+    pub bldg_ext_id: Option<Uuid>,
+    /// Room code, in any building unless `bldg_ext_id` is set
+    pub room_code: Option<String>,
+    /// Only meetings overlapping this time range
+    pub time_range: Option<TSTZRange>,
+}
+
+/// A meeting along with where it takes place.
+#[derive(Debug)]
+pub struct MeetingDetails {
+    pub meeting: Meeting,
+    pub room_code: String,
+    pub building_name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SlotStatus {
+    Free,
+    /// Booked by one or more other meetings
+    Taken,
+    /// Outside of the building's opening hours
+    Closed,
+    /// The room is blacked out
+    Unavailable,
+}
+
+/// A meeting that takes a time slot asked about.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictingMeeting {
+    pub ext_id: Uuid,
+    pub title: String,
+}
+
+/// Outcome of `Meeting::check_room_availability_v2` for one preferred time slot.
+#[derive(Debug)]
+pub struct SlotAvailability {
+    pub id: i64,
+    pub time_slot: TSTZRange,
+    pub status: SlotStatus,
+    /// The meetings that take the room, by start time
+    pub conflicts: Vec<ConflictingMeeting>,
+}
+
+/// A booking whose organizer and room have already been resolved.
+struct NewMeeting<'a> {
+    organizer_id: i64,
+    room_id: i64,
+    title: &'a str,
+    time_slot: TSTZRange,
+    series_id: Option<i64>,
+    occurrence_start: Option<DateTime<Utc>>,
+    /// Book as a hold lasting this long, as measured by the database's clock
+    hold_for: Option<Duration>,
+}
+
+impl Meeting {
+    /// Book a meeting, subject to the booking rules of the room's type.  A
+    /// meeting in a room whose type requires approval is booked as pending.
+    pub fn schedule_meeting(username: String,
+                            bldg_ext_id: Uuid,
+                            room_code: String,
+                            start_dt: String,
+                            end_dt: String,
+                            title: String,
+                            options: &ScheduleOptions,
+                            logger: &Logger,
+                            tx: &Transaction)
+                            -> Result<Meeting, MyError> {
+        let time_slot = parse_time_slot(&start_dt, &end_dt, bldg_ext_id, logger, tx)?;
+        let (organizer_id, room_id) =
+            Meeting::resolve_booking(&username, bldg_ext_id, &room_code, logger, tx)?;
+
+        let new_mtg = NewMeeting { organizer_id,
+                                   room_id,
+                                   title: &title,
+                                   time_slot,
+                                   series_id: None,
+                                   occurrence_start: None,
+                                   hold_for: None, };
+
+        Meeting::insert_meeting(&new_mtg, options, logger, tx)
+    }
+
+    /// Approve a pending meeting, confirming it.  Only administrators may do
+    /// so.  Approving a meeting that is already confirmed has no effect.
+    pub fn approve(ext_id: Uuid,
+                   acting_username: String,
+                   logger: &Logger,
+                   tx: &Transaction)
+                   -> Result<Meeting, MyError> {
+        let user_id = User::get_id(&acting_username, logger, tx)?;
+        User::require_admin(user_id, logger, tx)?;
+
+        let stmt = "
+		UPDATE testing.meeting
+		   SET status = 'confirmed'
+		 WHERE ext_id = $1
+		   AND status IN ('pending', 'confirmed')
+		RETURNING testing.meeting.*;";
+
+        let rows = tx.query(stmt, &[&ext_id]).map_err(|err| {
+            error!(logger, "Failed to approve meeting: DB Error.";
+					"step"=>"approve", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => {
+                let mtg = Meeting::from_row(&row)?;
+                info!(logger, "Approved Meeting: {}", mtg.ext_id; "by"=>&acting_username);
+                Ok(mtg)
+            }
+            None => {
+                info!(logger, "No pending meeting: {}", ext_id);
+                Err(MyError::MeetingError(MeetingError::NotFound))
+            }
+        }
+    }
+
+    /// Book a meeting, cancelling any meetings of a lower priority than
+    /// `options.priority` that stand in its way.  Only administrators may do
+    /// so.  Meetings of an equal or higher priority still cause a
+    /// `ScheduleConflict`, in which case nothing is cancelled.
+    ///
+    /// Returns the new meeting along with those it bumped.
+    pub fn schedule_bumping(username: String,
+                            bldg_ext_id: Uuid,
+                            room_code: String,
+                            start_dt: String,
+                            end_dt: String,
+                            title: String,
+                            options: &ScheduleOptions,
+                            logger: &Logger,
+                            tx: &Transaction)
+                            -> Result<(Meeting, Vec<BumpedMeeting>), MyError> {
+        let time_slot = parse_time_slot(&start_dt, &end_dt, bldg_ext_id, logger, tx)?;
+        let (organizer_id, room_id) =
+            Meeting::resolve_booking(&username, bldg_ext_id, &room_code, logger, tx)?;
+
+        User::require_admin(organizer_id, logger, tx)?;
+
+        let sp = tx.savepoint("schedule_bumping").map_err(|err| {
+            error!(logger, "Failed to create savepoint";
+					"step"=>"schedule_bumping", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        // bumped occurrences are not to be booked again with their series
+        let stmt = "
+		WITH bumped AS (
+			DELETE FROM testing.meeting
+			 WHERE room_id = $1
+			   AND blocked_slot && testing.buffered_slot($1, $2)
+			   AND priority < $3
+			RETURNING testing.meeting.*
+		), skipped AS (
+			INSERT INTO testing.meeting_series_exception(series_id, occurrence_start)
+			SELECT series_id, occurrence_start
+			  FROM bumped
+			 WHERE series_id IS NOT NULL
+			ON CONFLICT DO NOTHING
+		)
+		SELECT bumped.*, org.username AS organizer
+		  FROM bumped
+		  JOIN testing.users org
+			ON bumped.organizer_id = org.id
+	  ORDER BY lower(bumped.time_slot);";
+
+        let bumped = sp.query(stmt, &[&room_id, &time_slot, &options.priority.level()])
+                       .map_err(|err| {
+                           error!(logger, "Failed to bump meetings: DB Error.";
+								 "step"=>"schedule_bumping", "err"=>err.to_string());
+                           MyError::DBError(DBError::PGError(err))
+                       })?
+                       .iter()
+                       .map(|row| {
+                           Ok(BumpedMeeting { meeting: Meeting::from_row(&row)?,
+                                              organizer: row.get("organizer"), })
+                       })
+                       .collect::<Result<Vec<BumpedMeeting>, MyError>>()?;
+
+        let new_mtg = NewMeeting { organizer_id,
+                                   room_id,
+                                   title: &title,
+                                   time_slot,
+                                   series_id: None,
+                                   occurrence_start: None,
+                                   hold_for: None, };
+
+        let mtg = Meeting::insert_meeting(&new_mtg, options, logger, &sp)?;
+
+        sp.commit().map_err(|err| {
+            error!(logger, "Failed to release savepoint";
+					"step"=>"schedule_bumping", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        for bumped_mtg in &bumped {
+            info!(logger, "Bumped Meeting: {}", bumped_mtg.meeting.ext_id;
+				  "organizer"=>&bumped_mtg.organizer, "by"=>mtg.ext_id.to_string());
+        }
+
+        // the bumped meetings may have blocked more than the new one does
+        WaitlistEntry::promote(room_id, logger, tx)?;
+        Ok((mtg, bumped))
+    }
+
+    /// Book each of the requests in turn, e.g. when importing a schedule.
+    ///
+    /// Every request is booked within its own savepoint, so one that fails
+    /// leaves the others booked.  The outcome of each request is returned in
+    /// the order given.
+    pub fn schedule_many(requests: Vec<BookingRequest>,
+                         logger: &Logger,
+                         tx: &Transaction)
+                         -> Result<Vec<Result<Meeting, MyError>>, MyError> {
+        let mut results = Vec::with_capacity(requests.len());
+
+        for request in requests {
+            let sp = tx.savepoint("schedule_many").map_err(|err| {
+                error!(logger, "Failed to create savepoint";
+						"step"=>"schedule_many", "err"=>err.to_string());
+                MyError::DBError(DBError::PGError(err))
+            })?;
+
+            let result = Meeting::schedule_meeting(request.username,
+                                                   request.bldg_ext_id,
+                                                   request.room_code,
+                                                   request.start_dt,
+                                                   request.end_dt,
+                                                   request.title,
+                                                   &request.options,
+                                                   logger,
+                                                   &sp);
+
+            // a savepoint dropped without being committed is rolled back
+            if result.is_ok() {
+                sp.commit().map_err(|err| {
+                    error!(logger, "Failed to release savepoint";
+							"step"=>"schedule_many", "err"=>err.to_string());
+                    MyError::DBError(DBError::PGError(err))
+                })?;
+            }
+
+            results.push(result);
+        }
+
+        info!(logger, "Scheduled {} of {} meetings",
+              results.iter().filter(|result| result.is_ok()).count(), results.len());
+        Ok(results)
+    }
+
+    /// Tentatively book a room for `hold_for`, e.g. while confirming with
+    /// attendees.  The hold blocks the time slot like any other meeting until
+    /// it is either confirmed with `confirm_hold` or released once expired.
+    pub fn hold_room(username: String,
+                     bldg_ext_id: Uuid,
+                     room_code: String,
+                     start_dt: String,
+                     end_dt: String,
+                     title: String,
+                     hold_for: Duration,
+                     options: &ScheduleOptions,
+                     logger: &Logger,
+                     tx: &Transaction)
+                     -> Result<Meeting, MyError> {
+        if hold_for <= Duration::zero() {
+            info!(logger, "A hold must last for a positive duration");
+            return Err(MyError::ValueError(None));
+        }
+
+        let time_slot = parse_time_slot(&start_dt, &end_dt, bldg_ext_id, logger, tx)?;
+        let (organizer_id, room_id) =
+            Meeting::resolve_booking(&username, bldg_ext_id, &room_code, logger, tx)?;
+
+        let new_mtg = NewMeeting { organizer_id,
+                                   room_id,
+                                   title: &title,
+                                   time_slot,
+                                   series_id: None,
+                                   occurrence_start: None,
+                                   hold_for: Some(hold_for), };
+
+        Meeting::insert_meeting(&new_mtg, options, logger, tx)
+    }
+
+    /// Turn an unexpired hold into a confirmed meeting, or a pending one if
+    /// the room's type requires approval.  Confirming a meeting that is no
+    /// longer on hold has no effect.
+    pub fn confirm_hold(ext_id: Uuid,
+                        acting_username: String,
+                        logger: &Logger,
+                        tx: &Transaction)
+                        -> Result<Meeting, MyError> {
+        Meeting::authorize(ext_id, &acting_username, logger, tx)?;
+
+        let stmt = "
+		UPDATE testing.meeting
+		   SET status = CASE WHEN status <> 'hold' THEN status
+							 WHEN testing.requires_approval(room_id) THEN 'pending'
+							 ELSE 'confirmed' END,
+			   hold_expires_at = NULL
+		 WHERE ext_id = $1
+		   AND (status <> 'hold' OR hold_expires_at > clock_timestamp())
+		RETURNING testing.meeting.*;";
+
+        let rows = tx.query(stmt, &[&ext_id]).map_err(|err| {
+            error!(logger, "Failed to confirm hold: DB Error.";
+					"step"=>"confirm_hold", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => {
+                let mtg = Meeting::from_row(&row)?;
+                info!(logger, "Confirmed Meeting: {}", mtg.ext_id);
+                Ok(mtg)
+            }
+            None => {
+                info!(logger, "Hold on meeting {} has expired", ext_id);
+                Err(MyError::MeetingError(MeetingError::HoldExpired))
+            }
+        }
+    }
+
+    /// Release all holds that have expired, freeing their time slots for the
+    /// rooms' waitlists.  Meant to be run periodically.
+    pub fn release_expired_holds(logger: &Logger,
+                                 tx: &Transaction)
+                                 -> Result<Vec<Meeting>, MyError> {
+        let stmt = "
+		DELETE FROM testing.meeting
+		 WHERE status = 'hold'
+		   AND hold_expires_at <= clock_timestamp()
+		RETURNING testing.meeting.*;";
+
+        let released = tx.query(stmt, &[])
+                         .map_err(|err| {
+                             error!(logger, "Failed to release expired holds: DB Error.";
+								   "step"=>"release_expired_holds", "err"=>err.to_string());
+                             MyError::DBError(DBError::PGError(err))
+                         })
+                         .and_then(|rows: Rows| {
+                             let released = rows.iter()
+                                                .map(|row| Meeting::from_row(&row))
+                                                .collect::<Result<Vec<Meeting>, MyError>>()?;
+                             info!(logger, "Released {} expired holds", released.len());
+                             Ok(released)
+                         })?;
+
+        WaitlistEntry::promote_rooms(&released, logger, tx)?;
+        Ok(released)
+    }
+
+    /// Record that the meeting is taking place, which keeps its room from
+    /// being released as a no-show.  Only the organizer and attendees may
+    /// check in, from `CHECKIN_OPENS_MINUTES` before the meeting starts until
+    /// the building's grace period is up.  Checking in again has no effect.
+    pub fn check_in(ext_id: Uuid,
+                    acting_username: String,
+                    logger: &Logger,
+                    tx: &Transaction)
+                    -> Result<Meeting, MyError> {
+        let stmt = "
+		SELECT mtg.released_at IS NOT NULL,
+			   org.username = $2
+			   OR EXISTS (SELECT 1
+							FROM testing.meeting_attendee ma
+							JOIN testing.users u
+							  ON ma.user_id = u.id
+						   WHERE ma.meeting_id = mtg.id
+							 AND u.username = $2),
+			   clock_timestamp() >= lower(mtg.time_slot) - $3::integer * interval '1 minute'
+			   AND clock_timestamp()
+				   < lower(mtg.time_slot) + b.checkin_grace_minutes * interval '1 minute'
+		  FROM testing.meeting mtg
+		  JOIN testing.users org
+			ON mtg.organizer_id = org.id
+		  JOIN testing.room r
+			ON mtg.room_id = r.id
+		  JOIN testing.building b
+			ON r.building_id = b.id
+		 WHERE mtg.ext_id = $1
+		   FOR UPDATE OF mtg;";
+
+        let rows = tx.query(stmt, &[&ext_id, &acting_username, &CHECKIN_OPENS_MINUTES])
+                     .map_err(|err| {
+                         error!(logger, "Failed to look up meeting: DB Error.";
+								"step"=>"check_in", "err"=>err.to_string());
+                         MyError::DBError(DBError::PGError(err))
+                     })?;
+
+        let (released, permitted, in_window): (bool, bool, bool) = match rows.iter().next() {
+            Some(row) => (row.get(0), row.get(1), row.get(2)),
+            None => {
+                info!(logger, "No such meeting: {}", ext_id);
+                return Err(MyError::MeetingError(MeetingError::NotFound));
+            }
+        };
+
+        if !permitted {
+            info!(logger, "User {} may not check in to meeting {}", acting_username, ext_id);
+            return Err(MyError::MeetingError(MeetingError::NotPermitted));
+        }
+
+        if released {
+            info!(logger, "Room of meeting {} was already released", ext_id);
+            return Err(MyError::MeetingError(MeetingError::NoShowReleased));
+        }
+
+        if !in_window {
+            info!(logger, "Meeting {} is not open for check-in", ext_id);
+            return Err(MyError::MeetingError(MeetingError::OutsideCheckInWindow));
+        }
+
+        let stmt = "
+		UPDATE testing.meeting
+		   SET checked_in_at = COALESCE(checked_in_at, clock_timestamp())
+		 WHERE ext_id = $1
+		RETURNING testing.meeting.*;";
+
+        let rows = tx.query(stmt, &[&ext_id]).map_err(|err| {
+            error!(logger, "Failed to check in: DB Error.";
+					"step"=>"check_in", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => {
+                let mtg = Meeting::from_row(&row)?;
+                info!(logger, "Checked in to Meeting: {}", mtg.ext_id);
+                Ok(mtg)
+            }
+            None => {
+                error!(logger, "Error checking in to meeting in db: No record returned.";
+						"step"=>"check_in");
+                Err(MyError::DBError(DBError::NoRecord))
+            }
+        }
+    }
+
+    /// Release the rooms of ongoing meetings that nobody checked in to within
+    /// their building's grace period.  Each such meeting's time slot is cut
+    /// short at the check-in deadline, freeing the rest of it for other
+    /// bookings, starting with the room's waitlist.  Meant to be run
+    /// periodically.
+    pub fn release_no_shows(logger: &Logger, tx: &Transaction) -> Result<Vec<Meeting>, MyError> {
+        let stmt = "
+		WITH deadlines AS (
+			SELECT mtg.id,
+				   lower(mtg.time_slot) + b.checkin_grace_minutes * interval '1 minute' AS deadline
+			  FROM testing.meeting mtg
+			  JOIN testing.room r
+				ON mtg.room_id = r.id
+			  JOIN testing.building b
+				ON r.building_id = b.id
+			 WHERE mtg.status = 'confirmed'
+			   AND mtg.checked_in_at IS NULL
+			   AND mtg.released_at IS NULL
+		)
+		UPDATE testing.meeting mtg
+		   SET time_slot = mtg.time_slot * tstzrange(NULL, d.deadline, '[)'),
+			   released_at = clock_timestamp()
+		  FROM deadlines d
+		 WHERE mtg.id = d.id
+		   AND d.deadline <= clock_timestamp()
+		   AND upper(mtg.time_slot) > clock_timestamp()
+		RETURNING mtg.*;";
+
+        let released = tx.query(stmt, &[])
+                         .map_err(|err| {
+                             error!(logger, "Failed to release no-show meetings: DB Error.";
+								   "step"=>"release_no_shows", "err"=>err.to_string());
+                             MyError::DBError(DBError::PGError(err))
+                         })
+                         .and_then(|rows: Rows| {
+                             let released = rows.iter()
+                                                .map(|row| Meeting::from_row(&row))
+                                                .collect::<Result<Vec<Meeting>, MyError>>()?;
+                             info!(logger, "Released rooms of {} no-show meetings", released.len());
+                             Ok(released)
+                         })?;
+
+        WaitlistEntry::promote_rooms(&released, logger, tx)?;
+        Ok(released)
+    }
+
+    /// Look up the organizer and room ids of a booking request.
+    fn resolve_booking(username: &str,
+                       bldg_ext_id: Uuid,
+                       room_code: &str,
+                       logger: &Logger,
+                       tx: &Transaction)
+                       -> Result<(i64, i64), MyError> {
+        let stmt = "
+		SELECT (SELECT u.id
+				  FROM testing.users u
+				 WHERE u.username = $3),
+			   (SELECT r.id
+				  FROM testing.room r
+				  JOIN testing.building b
+					ON r.building_id = b.id
+				 WHERE r.code = $1
+				   AND b.ext_id = $2);";
+
+        let rows = tx.query(stmt, &[&room_code, &bldg_ext_id, &username]).map_err(|err| {
+            error!(logger, "Failed to schedule meeting: DB Error.";
+					"step"=>"resolve_booking", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let row = rows.get(0);
+        let organizer_id: Option<i64> = row.get(0);
+        let room_id: Option<i64> = row.get(1);
+        match (organizer_id, room_id) {
+            (Some(organizer_id), Some(room_id)) => Ok((organizer_id, room_id)),
+            (None, _) => {
+                info!(logger, "No such user: {}", username; "step"=>"resolve_booking");
+                Err(MyError::MeetingError(MeetingError::UnknownUser))
+            }
+            (_, None) => {
+                info!(logger, "No room {} in building {}", room_code, bldg_ext_id;
+						"step"=>"resolve_booking");
+                Err(MyError::MeetingError(MeetingError::RoomNotFound))
+            }
+        }
+    }
+
+    /// Meetings matching all of the filter's criteria, by start time.
+    pub fn list(filter: &MeetingFilter,
+                logger: &Logger,
+                tx: &Transaction)
+                -> Result<Vec<MeetingDetails>, MyError> {
+        let stmt = "
+		SELECT mtg.*, r.code AS room_code, b.name AS building_name
+		  FROM testing.meeting mtg
+		  JOIN testing.room r
+			ON mtg.room_id = r.id
+		  JOIN testing.building b
+			ON r.building_id = b.id
+		  JOIN testing.users org
+			ON mtg.organizer_id = org.id
+		 WHERE ($1::varchar IS NULL OR org.username = $1)
+		   AND ($2::uuid IS NULL OR b.ext_id = $2)
+		   AND ($3::varchar IS NULL OR r.code = $3)
+		   AND ($4::tstzrange IS NULL OR mtg.time_slot && $4)
+	  ORDER BY lower(mtg.time_slot), mtg.id;";
+
+        tx.query(stmt,
+                 &[&filter.organizer, &filter.bldg_ext_id, &filter.room_code, &filter.time_range])
+          .map_err(|err| {
+              error!(logger, "Failed to query for meetings: DB Error.";
+					"step"=>"list", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })
+          .and_then(|rows: Rows| {
+              rows.iter()
+                  .map(|row| {
+                      Ok(MeetingDetails { meeting: Meeting::from_row(&row)?,
+                                          room_code: row.get("room_code"),
+                                          building_name: row.get("building_name"), })
+                  })
+                  .collect()
+          })
+    }
+
+    /// Confirm what preferred timeslots are available for scheduling.
+    ///
+    /// The point of this function is to show how to import datasets for use
+    /// within SQL.
+    ///
+    /// Unparseable or empty time slots are reported as a `ValueError` with the
+    /// index of the first one.
+    pub fn check_room_availability_v1(room_cd: String,
+                                      bldg_ext_id: Uuid,
+                                      preferred_timeslots: Vec<(i64, String, String)>,
+                                      logger: &Logger,
+                                      tx: &Transaction)
+                                      -> Result<Vec<i64>, MyError> {
+        let mut p_ids = Vec::new();
+        let mut p_timeslots = Vec::new();
+        for (index, (id, start, end)) in preferred_timeslots.into_iter().enumerate() {
+            let time_slot = parse_time_slot(&start, &end, bldg_ext_id, logger, tx)
+                .map_err(|err| match err {
+                             MyError::ValueError(_) => MyError::ValueError(Some(index)),
+                             err => err,
+                         })?;
+
+            p_ids.push(id);
+            p_timeslots.push(time_slot);
+        }
+
+        Meeting::free_slot_ids(&room_cd, bldg_ext_id, &p_ids, &p_timeslots, logger, tx)
+    }
+
+    /// Ids of the candidate `time_slots` during which the room is free: within
+    /// the building's opening hours, not blacked out, and clear of other
+    /// meetings, taking the room's buffers into account.  This is what
+    /// `check_room_availability_v1` and `suggest_slots` count as a conflict.
+    fn free_slot_ids(room_cd: &str,
+                     bldg_ext_id: Uuid,
+                     ids: &[i64],
+                     time_slots: &[TSTZRange],
+                     logger: &Logger,
+                     tx: &Transaction)
+                     -> Result<Vec<i64>, MyError> {
+        let stmt = "
+		SELECT pref.id
+		  FROM unnest($1::bigint[], $2::tstzrange[]) WITH ORDINALITY pref(id, time_slot, n),
+			   testing.room r
+		  JOIN testing.building b
+			ON r.building_id = b.id
+		 WHERE r.code = $3
+		   AND b.ext_id = $4
+		   AND testing.within_opening_hours(b.id, pref.time_slot)
+		   AND NOT testing.room_blacked_out(r.id, testing.buffered_slot(r.id, pref.time_slot))
+		   AND not exists (SELECT true
+		 					 FROM testing.meeting mtg
+							WHERE mtg.room_id = r.id
+							  AND mtg.blocked_slot && testing.buffered_slot(r.id,
+																			pref.time_slot))
+	  ORDER BY pref.n;";
+
+        tx.query(stmt, &[&ids, &time_slots, &room_cd, &bldg_ext_id])
+          .map_err(|err| {
+              error!(logger, "Failed to check room availability: DB Error.";
+					"step"=>"free_slot_ids", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })
+          .map(|rows: Rows| rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    /// Check each of the preferred time slots, given as (id, start, end), for
+    /// whether the room is free, listing the meetings that take it otherwise.
+    ///
+    /// As with `check_room_availability_v1`, unparseable or empty time slots
+    /// are reported as a `ValueError` with the index of the first one.  A
+    /// room lacking any of the `required_amenities`, given by code, is
+    /// reported as `MissingAmenities`.
+    pub fn check_room_availability_v2(room_cd: String,
+                                      bldg_ext_id: Uuid,
+                                      preferred_timeslots: Vec<(i64, String, String)>,
+                                      required_amenities: &[String],
+                                      logger: &Logger,
+                                      tx: &Transaction)
+                                      -> Result<Vec<SlotAvailability>, MyError> {
+        let room = Room::get_room(bldg_ext_id, room_cd, logger, tx)?;
+        Room::require_amenities(room.id, required_amenities, logger, tx)?;
+
+        let mut availability = Vec::new();
+        for (index, (id, start, end)) in preferred_timeslots.into_iter().enumerate() {
+            let time_slot = parse_time_slot(&start, &end, bldg_ext_id, logger, tx)
+                .map_err(|err| match err {
+                             MyError::ValueError(_) => MyError::ValueError(Some(index)),
+                             err => err,
+                         })?;
+
+            availability.push(SlotAvailability { id,
+                                                 time_slot,
+                                                 status: SlotStatus::Free,
+                                                 conflicts: Vec::new(), });
+        }
+
+        let p_timeslots = availability.iter()
+                                      .map(|slot| slot.time_slot)
+                                      .collect::<Vec<TSTZRange>>();
+
+        let stmt = "
+		SELECT pref.n,
+			   testing.within_opening_hours($3, pref.time_slot),
+			   testing.room_blacked_out($2, testing.buffered_slot($2, pref.time_slot)),
+			   mtg.ext_id,
+			   mtg.title
+		  FROM unnest($1::tstzrange[]) WITH ORDINALITY pref(time_slot, n)
+	 LEFT JOIN testing.meeting mtg
+			ON mtg.room_id = $2
+		   AND mtg.blocked_slot && testing.buffered_slot($2, pref.time_slot)
+	  ORDER BY pref.n, lower(mtg.time_slot);";
+
+        let rows = tx.query(stmt, &[&p_timeslots, &room.id, &room.building_id]).map_err(|err| {
+            error!(logger, "Failed to check room availability: DB Error.";
+					"step"=>"check_room_availability_v2", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        for row in rows.iter() {
+            let n: i64 = row.get(0);
+            let open: bool = row.get(1);
+            let blacked_out: bool = row.get(2);
+            let ext_id: Option<Uuid> = row.get(3);
+
+            let slot = &mut availability[n as usize - 1];
+            if let Some(ext_id) = ext_id {
+                slot.conflicts.push(ConflictingMeeting { ext_id,
+                                                         title: row.get(4), });
+            }
+            slot.status = if !open {
+                SlotStatus::Closed
+            } else if blacked_out {
+                SlotStatus::Unavailable
+            } else if !slot.conflicts.is_empty() {
+                SlotStatus::Taken
+            } else {
+                SlotStatus::Free
+            };
+        }
+
+        Ok(availability)
+    }
+
+    /// Suggest up to `max_results` free time slots of the given duration in the
+    /// room, earliest first, optionally limited to working hours in the
+    /// building's local time.
+    ///
+    /// Candidates start every `SUGGESTION_STEP_MINUTES` and are checked with
+    /// the same query as `check_room_availability_v1`; suggestions do not
+    /// overlap one another.  A room lacking any of the `required_amenities`,
+    /// given by code, is reported as `MissingAmenities`.
+    pub fn suggest_slots(bldg_ext_id: Uuid,
+                         room_code: String,
+                         duration: Duration,
+                         search_window: TSTZRange,
+                         max_results: usize,
+                         working_hours: Option<&WorkingHours>,
+                         required_amenities: &[String],
+                         logger: &Logger,
+                         tx: &Transaction)
+                         -> Result<Vec<TSTZRange>, MyError> {
+        let bounded = search_window.lower().is_some() && search_window.upper().is_some();
+        if duration <= Duration::zero() || !bounded {
+            info!(logger, "Slot suggestions need a positive duration and a bounded window");
+            return Err(MyError::ValueError(None));
+        }
+
+        let room = Room::get_room(bldg_ext_id, room_code.clone(), logger, tx)?;
+        Room::require_amenities(room.id, required_amenities, logger, tx)?;
+
+        let windows = match working_hours {
+            Some(hours) => {
+                let windows = Building::get_working_windows(bldg_ext_id,
+                                                            hours,
+                                                            &search_window,
+                                                            logger,
+                                                            tx)?;
+                timeslots::mask(&windows, &[search_window])
+            }
+            None => vec![search_window],
+        };
+
+        let step = Duration::minutes(SUGGESTION_STEP_MINUTES);
+        let candidates = timeslots::candidate_slots(&windows, duration, step);
+        let ids = (0..candidates.len() as i64).collect::<Vec<i64>>();
+        let free = Meeting::free_slot_ids(&room_code, bldg_ext_id, &ids, &candidates, logger, tx)?;
+
+        let mut suggestions: Vec<TSTZRange> = Vec::new();
+        for id in free {
+            if suggestions.len() >= max_results {
+                break;
+            }
+
+            let candidate = candidates[id as usize];
+            let overlaps = suggestions.last()
+                                      .is_some_and(|last| !last.intersect(&candidate).is_empty());
+            if !overlaps {
+                suggestions.push(candidate);
+            }
+        }
+
+        Ok(suggestions)
+    }
+
+    /// Cancel a meeting, releasing its time slot for other bookings, starting
+    /// with the room's waitlist.  A cancelled occurrence of a series is
+    /// recorded as a series exception.
+    ///
+    /// Only the meeting's organizer or an admin may cancel it.
+    pub fn cancel_meeting(ext_id: Uuid,
+                          acting_username: String,
+                          logger: &Logger,
+                          tx: &Transaction)
+                          -> Result<Meeting, MyError> {
+        Meeting::authorize(ext_id, &acting_username, logger, tx)?;
+
+        let stmt = "
+		WITH cancelled AS (
+			DELETE FROM testing.meeting
+			 WHERE ext_id = $1
+			RETURNING testing.meeting.*
+		), skipped AS (
+			INSERT INTO testing.meeting_series_exception(series_id, occurrence_start)
+			SELECT series_id, occurrence_start
+			  FROM cancelled
+			 WHERE series_id IS NOT NULL
+			ON CONFLICT DO NOTHING
+		)
+		SELECT * FROM cancelled;";
+
+        let mtg = tx.query(stmt, &[&ext_id])
+                    .map_err(|err| {
+                        error!(logger, "Failed to cancel meeting: DB Error.";
+							  "step"=>"cancel_meeting", "err"=>err.to_string());
+                        MyError::DBError(DBError::PGError(err))
+                    })
+                    .and_then(|rows: Rows| {
+                        rows.into_iter()
+                            .next()
+                            .ok_or_else(|| {
+                                error!(logger,
+                                       "Error cancelling meeting in db: No record returned.";
+  									  "step"=>"cancel_meeting");
+                                MyError::DBError(DBError::NoRecord)
+                            })
+                            .and_then(|row: Row| {
+                                let mtg = Meeting::from_row(&row)?;
+                                info!(logger, "Cancelled Meeting: {}", mtg.ext_id;
+                                      "by"=>&acting_username);
+                                Ok(mtg)
+                            })
+                    })?;
+
+        WaitlistEntry::promote(mtg.room_id, logger, tx)?;
+        Ok(mtg)
+    }
+
+    /// Move a meeting to another time slot and/or room, keeping its ext_id.
+    /// A moved occurrence of a series is flagged as modified.  Time released
+    /// in the original room goes to its waitlist.
+    ///
+    /// Attendee conflicts are handled per `options.conflict_policy`, and a
+    /// headcount given in the options replaces the meeting's.  The booking
+    /// rules of the new room's type apply, and a meeting moved within or into
+    /// a room that requires approval is pending until approved again.
+    ///
+    /// Only the meeting's organizer or an admin may reschedule it.
+    ///
+    /// The update runs within a savepoint so that a scheduling conflict leaves
+    /// the original booking, and the caller's transaction, intact.
+    pub fn reschedule(ext_id: Uuid,
+                      acting_username: String,
+                      new_room_code: String,
+                      new_bldg_ext_id: Uuid,
+                      new_start: String,
+                      new_end: String,
+                      options: &ScheduleOptions,
+                      logger: &Logger,
+                      tx: &Transaction)
+                      -> Result<Meeting, MyError> {
+        Meeting::authorize(ext_id, &acting_username, logger, tx)?;
+        let (_, new_room_id) = Meeting::resolve_booking(&acting_username,
+                                                        new_bldg_ext_id,
+                                                        &new_room_code,
+                                                        logger,
+                                                        tx)?;
+        let time_slot = parse_time_slot(&new_start, &new_end, new_bldg_ext_id, logger, tx)?;
+
+        let sp = tx.savepoint("reschedule").map_err(|err| {
+            error!(logger, "Failed to create savepoint";
+					"step"=>"reschedule", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let stmt = "
+		SELECT room_id
+		  FROM testing.meeting
+		 WHERE ext_id = $1
+		   FOR UPDATE;";
+
+        let found = sp.query(stmt, &[&ext_id]).map_err(|err| {
+            error!(logger, "Failed to look up meeting: DB Error.";
+					"step"=>"reschedule", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let old_room_id: i64 = match found.iter().next() {
+            Some(row) => row.get(0),
+            None => {
+                info!(logger, "No such meeting: {}", ext_id);
+                return Err(MyError::MeetingError(MeetingError::NotFound));
+            }
+        };
+
+        let stmt = "
+		UPDATE testing.meeting mtg
+		   SET room_id = $2,
+			   time_slot = $1,
+			   is_modified = mtg.series_id IS NOT NULL,
+			   headcount = COALESCE($4, mtg.headcount),
+			   status = CASE WHEN mtg.status = 'hold' THEN 'hold'
+							 WHEN testing.requires_approval($2) THEN 'pending'
+							 ELSE 'confirmed' END
+		 WHERE mtg.ext_id = $3
+		RETURNING mtg.*;";
+
+        let rows = sp.query(stmt, &[&time_slot, &new_room_id, &ext_id, &options.headcount])
+                     .map_err(|err| map_booking_err(err, "reschedule", logger))?;
+
+        let mtg = match rows.iter().next() {
+            Some(row) => Meeting::from_row(&row)?,
+            None => {
+                error!(logger, "Error rescheduling meeting in db: No record returned.";
+						"step"=>"reschedule");
+                return Err(MyError::DBError(DBError::NoRecord));
+            }
+        };
+
+        Meeting::check_booking_rules(mtg.room_id, &mtg.time_slot, logger, &sp)?;
+        Meeting::check_opening_hours(mtg.room_id, &mtg.time_slot, logger, &sp)?;
+        Meeting::check_capacity(mtg.id, logger, &sp)?;
+        Meeting::enforce_conflict_policy(mtg.id, None, options.conflict_policy, logger, &sp)?;
+
+        sp.commit().map_err(|err| {
+            error!(logger, "Failed to release savepoint";
+					"step"=>"reschedule", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        info!(logger, "Rescheduled Meeting: {}", mtg.ext_id);
+
+        WaitlistEntry::promote(old_room_id, logger, tx)?;
+        Ok(mtg)
+    }
+
+    /// Invite users, identified by their ext_id, to a meeting.  Users who are
+    /// already invited keep their current response.
+    ///
+    /// Required attendees count towards double-booking detection, which is
+    /// handled according to `policy`.
+    pub fn invite(ext_id: Uuid,
+                  user_ext_ids: Vec<Uuid>,
+                  required: bool,
+                  policy: ConflictPolicy,
+                  logger: &Logger,
+                  tx: &Transaction)
+                  -> Result<Vec<Attendee>, MyError> {
+        let stmt = "
+		SELECT mtg.id,
+			   (SELECT count(*)
+				  FROM testing.users u
+				 WHERE u.ext_id = ANY($2))
+			 = (SELECT count(DISTINCT invitee)
+				  FROM unnest($2::uuid[]) invitee)
+		  FROM testing.meeting mtg
+		 WHERE mtg.ext_id = $1;";
+
+        let rows = tx.query(stmt, &[&ext_id, &user_ext_ids]).map_err(|err| {
+            error!(logger, "Failed to look up meeting: DB Error.";
+					"step"=>"invite", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let meeting_id: i64 = match rows.iter().next() {
+            Some(row) => {
+                let all_users_exist: bool = row.get(1);
+                if !all_users_exist {
+                    info!(logger, "Cannot invite unknown users to meeting {}", ext_id);
+                    return Err(MyError::DBError(DBError::NoRecord));
+                }
+                row.get(0)
+            }
+            None => {
+                info!(logger, "No such meeting: {}", ext_id);
+                return Err(MyError::MeetingError(MeetingError::NotFound));
+            }
+        };
+
+        let sp = tx.savepoint("invite").map_err(|err| {
+            error!(logger, "Failed to create savepoint";
+					"step"=>"invite", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let stmt = "
+		INSERT INTO testing.meeting_attendee(meeting_id, user_id, is_required)
+		SELECT $1, u.id, $3
+		  FROM testing.users u
+		 WHERE u.ext_id = ANY($2)
+		ON CONFLICT (meeting_id, user_id) DO NOTHING;";
+
+        let invited = sp.execute(stmt, &[&meeting_id, &user_ext_ids, &required]).map_err(|err| {
+            error!(logger, "Failed to invite attendees: DB Error.";
+					"step"=>"invite", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        Meeting::check_capacity(meeting_id, logger, &sp)?;
+        Meeting::enforce_conflict_policy(meeting_id, Some(&user_ext_ids), policy, logger, &sp)?;
+
+        sp.commit().map_err(|err| {
+            error!(logger, "Failed to release savepoint";
+					"step"=>"invite", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+        info!(logger, "Invited {} attendees to Meeting: {}", invited, ext_id);
+
+        Meeting::get_attendees(ext_id, logger, tx)
+    }
+
+    /// Record an invited user's response to a meeting invitation.
+    pub fn respond(ext_id: Uuid,
+                   user_ext_id: Uuid,
+                   response: Rsvp,
+                   logger: &Logger,
+                   tx: &Transaction)
+                   -> Result<Attendee, MyError> {
+        let status = RsvpStatus::from(response);
+
+        let stmt = "
+		UPDATE testing.meeting_attendee a
+		   SET status = $3
+		  FROM testing.meeting mtg, testing.users u
+		 WHERE a.meeting_id = mtg.id
+		   AND a.user_id = u.id
+		   AND mtg.ext_id = $1
+		   AND u.ext_id = $2
+		RETURNING u.id, u.ext_id, u.first_name, u.last_name, u.username, u.is_admin,
+				  a.status, a.is_required;";
+
+        let rows = tx.query(stmt, &[&ext_id, &user_ext_id, &status.code()]).map_err(|err| {
+            error!(logger, "Failed to record meeting response: DB Error.";
+					"step"=>"respond", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => {
+                let attendee = Attendee::from_row(&row);
+                info!(logger, "User {} responded to Meeting {}", attendee.user.username, ext_id;
+                      "status"=>status.code());
+                Ok(attendee)
+            }
+            None => {
+                info!(logger, "User {} is not invited to meeting {}", user_ext_id, ext_id);
+                Err(MyError::MeetingError(MeetingError::NotFound))
+            }
+        }
+    }
+
+    pub fn get_attendees(ext_id: Uuid,
+                         logger: &Logger,
+                         tx: &Transaction)
+                         -> Result<Vec<Attendee>, MyError> {
+        let stmt = "
+		SELECT u.id, u.ext_id, u.first_name, u.last_name, u.username, u.is_admin,
+			   a.status, a.is_required
+		  FROM testing.meeting_attendee a
+		  JOIN testing.meeting mtg
+			ON a.meeting_id = mtg.id
+		  JOIN testing.users u
+			ON a.user_id = u.id
+		 WHERE mtg.ext_id = $1
+	  ORDER BY u.username;";
+
+        tx.query(stmt, &[&ext_id])
+          .map_err(|err| {
+              error!(logger, "Failed to query for attendees: DB Error.";
+					"step"=>"get_attendees", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })
+          .map(|rows: Rows| rows.iter().map(|row| Attendee::from_row(&row)).collect())
+    }
+
+    /// Book a meeting for an already resolved organizer and room, subject to
+    /// the booking rules of the room's type.  Unless booked as a hold, a
+    /// meeting in a room whose type requires approval is booked as pending.
+    /// The insert runs within a savepoint so that a conflict does not abort
+    /// the caller's transaction.
+    fn insert_meeting(new_mtg: &NewMeeting,
+                      options: &ScheduleOptions,
+                      logger: &Logger,
+                      tx: &Transaction)
+                      -> Result<Meeting, MyError> {
+        // only administrators may raise a meeting above routine priority
+        if options.priority > Priority::Routine {
+            User::require_admin(new_mtg.organizer_id, logger, tx)?;
+        }
+        Meeting::check_booking_rules(new_mtg.room_id, &new_mtg.time_slot, logger, tx)?;
+        Meeting::check_opening_hours(new_mtg.room_id, &new_mtg.time_slot, logger, tx)?;
+
+        let sp = tx.savepoint("insert_meeting").map_err(|err| {
+            error!(logger, "Failed to create savepoint";
+					"step"=>"insert_meeting", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let stmt = "
+		INSERT INTO testing.meeting(organizer_id, room_id, title, time_slot,
+									series_id, occurrence_start, headcount,
+									status, hold_expires_at, priority)
+		VALUES ($1, $2, $3, $4, $5, $6, $7,
+				CASE WHEN $8::bigint IS NOT NULL THEN 'hold'
+					 WHEN testing.requires_approval($2) THEN 'pending'
+					 ELSE 'confirmed' END,
+				clock_timestamp() + $8 * interval '1 millisecond', $9)
+		RETURNING testing.meeting.*;";
+
+        let rows = sp.query(stmt,
+                            &[&new_mtg.organizer_id,
+                              &new_mtg.room_id,
+                              &new_mtg.title,
+                              &new_mtg.time_slot,
+                              &new_mtg.series_id,
+                              &new_mtg.occurrence_start,
+                              &options.headcount,
+                              &new_mtg.hold_for.map(|hold_for| hold_for.num_milliseconds()),
+                              &options.priority.level()])
+                     .map_err(|err| map_booking_err(err, "insert_meeting", logger))?;
+
+        let mtg = match rows.iter().next() {
+            Some(row) => Meeting::from_row(&row)?,
+            None => {
+                error!(logger, "Error scheduling meeting in db: No record returned.";
+						"step"=>"insert_meeting");
+                return Err(MyError::DBError(DBError::NoRecord));
+            }
+        };
+
+        // the room is free, but it may be too small or the organizer may be
+        // busy elsewhere
+        Meeting::check_capacity(mtg.id, logger, &sp)?;
+        Meeting::enforce_conflict_policy(mtg.id, None, options.conflict_policy, logger, &sp)?;
+
+        sp.commit().map_err(|err| {
+            error!(logger, "Failed to release savepoint";
+					"step"=>"insert_meeting", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        info!(logger, "Scheduled Meeting: {}", mtg.ext_id);
+        Ok(mtg)
+    }
+
+    /// Find other meetings that the organizer or required attendees of this
+    /// meeting are committed to at the same time.
+    pub fn get_attendee_conflicts(ext_id: Uuid,
+                                  logger: &Logger,
+                                  tx: &Transaction)
+                                  -> Result<Vec<PersonConflict>, MyError> {
+        let stmt = "
+		SELECT id
+		  FROM testing.meeting
+		 WHERE ext_id = $1;";
+
+        let rows = tx.query(stmt, &[&ext_id]).map_err(|err| {
+            error!(logger, "Failed to look up meeting: DB Error.";
+					"step"=>"get_attendee_conflicts", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => Meeting::person_conflicts(row.get(0), None, logger, tx),
+            None => {
+                info!(logger, "No such meeting: {}", ext_id);
+                Err(MyError::MeetingError(MeetingError::NotFound))
+            }
+        }
+    }
+
+    /// Conflicts of the meeting's participants, optionally limited to the
+    /// users with the given ext_ids.  A participant is the organizer or a
+    /// required attendee who has not declined.
+    fn person_conflicts(meeting_id: i64,
+                        user_ext_ids: Option<&Vec<Uuid>>,
+                        logger: &Logger,
+                        tx: &Transaction)
+                        -> Result<Vec<PersonConflict>, MyError> {
+        let stmt = "
+		SELECT DISTINCT u.ext_id, other.ext_id, lower(other.time_slot)
+		  FROM testing.meeting this
+		  JOIN testing.meeting other
+			ON other.id <> this.id
+		   AND other.time_slot && this.time_slot
+		  JOIN testing.users u
+			ON (u.id = this.organizer_id
+				OR EXISTS (SELECT true
+							 FROM testing.meeting_attendee a
+							WHERE a.meeting_id = this.id
+							  AND a.user_id = u.id
+							  AND a.is_required
+							  AND a.status <> 'declined'))
+		   AND (u.id = other.organizer_id
+				OR EXISTS (SELECT true
+							 FROM testing.meeting_attendee a
+							WHERE a.meeting_id = other.id
+							  AND a.user_id = u.id
+							  AND a.is_required
+							  AND a.status <> 'declined'))
+		 WHERE this.id = $1
+		   AND ($2::uuid[] IS NULL OR u.ext_id = ANY($2))
+	  ORDER BY lower(other.time_slot);";
+
+        tx.query(stmt, &[&meeting_id, &user_ext_ids])
+          .map_err(|err| {
+              error!(logger, "Failed to check attendee conflicts: DB Error.";
+					"step"=>"person_conflicts", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })
+          .map(|rows: Rows| {
+              rows.iter()
+                  .map(|row| PersonConflict { user: row.get(0),
+                                              conflicting_meeting: row.get(1), })
+                  .collect()
+          })
+    }
+
+    /// Confirm that the time slot follows the booking rules of the room's
+    /// type, if it has one.  How far ahead it is goes by the database's clock.
+    fn check_booking_rules(room_id: i64,
+                           time_slot: &TSTZRange,
+                           logger: &Logger,
+                           tx: &Transaction)
+                           -> Result<(), MyError> {
+        let stmt = "
+		SELECT t.max_booking_minutes,
+			   COALESCE(upper($2::tstzrange) - lower($2::tstzrange)
+						> t.max_booking_minutes * interval '1 minute',
+						false),
+			   t.advance_booking_days,
+			   COALESCE(lower($2) > clock_timestamp() + t.advance_booking_days * interval '1 day',
+						false)
+		  FROM testing.room r
+		  JOIN testing.room_type t
+			ON r.room_type_id = t.id
+		 WHERE r.id = $1;";
+
+        let rows = tx.query(stmt, &[&room_id, time_slot]).map_err(|err| {
+            error!(logger, "Failed to look up room type: DB Error.";
+					"step"=>"check_booking_rules", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let row = match rows.iter().next() {
+            Some(row) => row,
+            None => return Ok(()),
+        };
+
+        let too_long: bool = row.get(1);
+        if too_long {
+            let max_minutes: i32 = row.get(0);
+            info!(logger, "Booking is longer than the room type allows");
+            return Err(MyError::MeetingError(MeetingError::BookingTooLong { max_minutes }));
+        }
+
+        let too_far_ahead: bool = row.get(3);
+        if too_far_ahead {
+            let advance_days: i32 = row.get(2);
+            info!(logger, "Booking is further ahead than the room type allows");
+            let err = MeetingError::OutsideBookingWindow { advance_days };
+            return Err(MyError::MeetingError(err));
+        }
+
+        Ok(())
+    }
+
+    /// Confirm that the room's building is open for the entire time slot.
+    fn check_opening_hours(room_id: i64,
+                           time_slot: &TSTZRange,
+                           logger: &Logger,
+                           tx: &Transaction)
+                           -> Result<(), MyError> {
+        let stmt = "
+		SELECT testing.within_opening_hours(r.building_id, $2)
+		  FROM testing.room r
+		 WHERE r.id = $1;";
+
+        let rows = tx.query(stmt, &[&room_id, time_slot]).map_err(|err| {
+            error!(logger, "Failed to check opening hours: DB Error.";
+					"step"=>"check_opening_hours", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let open: bool = match rows.iter().next() {
+            Some(row) => row.get(0),
+            None => return Err(MyError::DBError(DBError::NoRecord)),
+        };
+
+        if !open {
+            info!(logger, "Building is closed during the requested time slot");
+            return Err(MyError::MeetingError(MeetingError::OutsideOpeningHours));
+        }
+
+        Ok(())
+    }
+
+    /// Confirm that the meeting's room can seat the larger of its expected
+    /// headcount and its organizer plus attendees who have not declined.
+    fn check_capacity(meeting_id: i64, logger: &Logger, tx: &Transaction) -> Result<(), MyError> {
+        let stmt = "
+		SELECT r.capacity,
+			   GREATEST(COALESCE(mtg.headcount, 0),
+						1 + (SELECT count(*)
+							   FROM testing.meeting_attendee a
+							  WHERE a.meeting_id = mtg.id
+								AND a.status <> 'declined'))::integer
+		  FROM testing.meeting mtg
+		  JOIN testing.room r
+			ON mtg.room_id = r.id
+		 WHERE mtg.id = $1;";
+
+        let rows = tx.query(stmt, &[&meeting_id]).map_err(|err| {
+            error!(logger, "Failed to check room capacity: DB Error.";
+					"step"=>"check_capacity", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let (capacity, headcount): (i32, i32) = match rows.iter().next() {
+            Some(row) => (row.get(0), row.get(1)),
+            None => return Err(MyError::MeetingError(MeetingError::NotFound)),
+        };
+
+        if headcount > capacity {
+            info!(logger, "Room capacity of {} exceeded by headcount of {}", capacity, headcount);
+            return Err(MyError::MeetingError(MeetingError::CapacityExceeded { capacity,
+                                                                              headcount, }));
+        }
+
+        Ok(())
+    }
+
+    /// Log any participant conflicts of the meeting, failing on the first one
+    /// if the policy says so.
+    fn enforce_conflict_policy(meeting_id: i64,
+                               user_ext_ids: Option<&Vec<Uuid>>,
+                               policy: ConflictPolicy,
+                               logger: &Logger,
+                               tx: &Transaction)
+                               -> Result<(), MyError> {
+        let conflicts = Meeting::person_conflicts(meeting_id, user_ext_ids, logger, tx)?;
+
+        for conflict in &conflicts {
+            warn!(logger, "User {} is already booked in meeting {}",
+                  conflict.user, conflict.conflicting_meeting);
+        }
+
+        match (policy, conflicts.into_iter().next()) {
+            (ConflictPolicy::Error, Some(conflict)) => {
+                Err(MyError::MeetingError(MeetingError::AttendeeConflict {
+                    user: conflict.user,
+                    conflicting_meeting: conflict.conflicting_meeting,
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Confirm that the acting user may modify the meeting, i.e. that they are
+    /// its organizer or an admin.  The meeting row stays locked until the
+    /// transaction ends.
+    fn authorize(ext_id: Uuid,
+                 acting_username: &str,
+                 logger: &Logger,
+                 tx: &Transaction)
+                 -> Result<(), MyError> {
+        let stmt = "
+		SELECT org.username = $2 OR COALESCE(actor.is_admin, false)
+		  FROM testing.meeting mtg
+		  JOIN testing.users org
+			ON mtg.organizer_id = org.id
+	 LEFT JOIN testing.users actor
+			ON actor.username = $2
+		 WHERE mtg.ext_id = $1
+		   FOR UPDATE OF mtg;";
+
+        let rows = tx.query(stmt, &[&ext_id, &acting_username]).map_err(|err| {
+            error!(logger, "Failed to look up meeting: DB Error.";
+					"step"=>"authorize", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let permitted: bool = match rows.iter().next() {
+            Some(row) => row.get(0),
+            None => {
+                info!(logger, "No such meeting: {}", ext_id);
+                return Err(MyError::MeetingError(MeetingError::NotFound));
+            }
+        };
+
+        if !permitted {
+            info!(logger, "User {} may not modify meeting {}", acting_username, ext_id);
+            return Err(MyError::MeetingError(MeetingError::NotPermitted));
+        }
+
+        Ok(())
+    }
+
+    fn from_row(row: &Row) -> Result<Meeting, MyError> {
+        let status: String = row.get("status");
+        let time_slot: TSTZRange = row.get("time_slot");
+        let start = time_slot.lower().expect("time slots are bounded").value;
+        let end = time_slot.upper().expect("time slots are bounded").value;
+
+        Ok(Meeting { id: row.get("id"),
+                     ext_id: row.get("ext_id"),
+                     organizer_id: row.get("organizer_id"),
+                     room_id: row.get("room_id"),
+                     title: row.get("title"),
+                     time_slot,
+                     series_id: row.get("series_id"),
+                     occurrence_start: row.get("occurrence_start"),
+                     is_modified: row.get("is_modified"),
+                     headcount: row.get("headcount"),
+                     status: MeetingStatus::from_code(&status).expect("constrained by db check"),
+                     hold_expires_at: row.get("hold_expires_at"),
+                     priority: Priority::from_level(row.get("priority"))
+                               .expect("constrained by db check"),
+                     checked_in_at: row.get("checked_in_at"),
+                     released_at: row.get("released_at"),
+                     local_start: to_local(start, row.get("local_start"))?,
+                     local_end: to_local(end, row.get("local_end"))?, })
+    }
+
+    /// None for standalone meetings.
+    pub fn occurrence_kind(&self) -> Option<OccurrenceKind> {
+        match (self.series_id, self.is_modified) {
+            (None, _) => None,
+            (Some(_), false) => Some(OccurrenceKind::Generated),
+            (Some(_), true) => Some(OccurrenceKind::Modified),
+        }
+    }
+}
+
+/// An invitee's response to a meeting invitation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RsvpStatus {
+    Invited,
+    Accepted,
+    Declined,
+    Tentative,
+}
+impl RsvpStatus {
+    pub fn code(&self) -> &'static str {
+        match *self {
+            RsvpStatus::Invited => "invited",
+            RsvpStatus::Accepted => "accepted",
+            RsvpStatus::Declined => "declined",
+            RsvpStatus::Tentative => "tentative",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<RsvpStatus> {
+        match code {
+            "invited" => Some(RsvpStatus::Invited),
+            "accepted" => Some(RsvpStatus::Accepted),
+            "declined" => Some(RsvpStatus::Declined),
+            "tentative" => Some(RsvpStatus::Tentative),
+            _ => None,
+        }
+    }
+}
+
+/// The responses an invitee may give through `Meeting::respond`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rsvp {
+    Accept,
+    Decline,
+    Tentative,
+}
+impl From<Rsvp> for RsvpStatus {
+    fn from(response: Rsvp) -> RsvpStatus {
+        match response {
+            Rsvp::Accept => RsvpStatus::Accepted,
+            Rsvp::Decline => RsvpStatus::Declined,
+            Rsvp::Tentative => RsvpStatus::Tentative,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Attendee {
+    pub user: User,
+    pub status: RsvpStatus,
+    pub is_required: bool,
+}
+impl Attendee {
+    fn from_row(row: &Row) -> Attendee {
+        let status: String = row.get("status");
+
+        Attendee { user: User { id: row.get("id"),
+                                ext_id: row.get("ext_id"),
+                                first_name: row.get("first_name"),
+                                last_name: row.get("last_name"),
+                                username: row.get("username"),
+                                is_admin: row.get("is_admin"), },
+                   status: RsvpStatus::from_code(&status).expect("constrained by db check"),
+                   is_required: row.get("is_required"), }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WaitlistStatus {
+    Waiting,
+    /// Booked automatically once the room became free
+    Booked,
+    /// Could not be booked even though the room became free, e.g. for
+    /// exceeding its capacity
+    Failed,
+}
+impl WaitlistStatus {
+    pub fn code(&self) -> &'static str {
+        match *self {
+            WaitlistStatus::Waiting => "waiting",
+            WaitlistStatus::Booked => "booked",
+            WaitlistStatus::Failed => "failed",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<WaitlistStatus> {
+        match code {
+            "waiting" => Some(WaitlistStatus::Waiting),
+            "booked" => Some(WaitlistStatus::Booked),
+            "failed" => Some(WaitlistStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// A booking request waiting for its room to become free.
+#[derive(Debug)]
+pub struct WaitlistEntry {
+    pub id: i64,
+    pub ext_id: Uuid,
+    pub requester_id: i64,
+    pub room_id: i64,
+    pub title: String,
+    pub time_slot: TSTZRange,
+    pub headcount: Option<i32>,
+    /// Applied when the request is booked
+    pub conflict_policy: ConflictPolicy,
+    pub status: WaitlistStatus,
+    /// The meeting booked for the request, once booked
+    pub meeting_ext_id: Option<Uuid>,
+}
+impl WaitlistEntry {
+    /// Queue a booking request, typically after it failed with a
+    /// `ScheduleConflict`.  Whenever a meeting in the room is cancelled,
+    /// shortened or moved, waiting requests that now fit are booked on a
+    /// first-come, first-served basis.
+    ///
+    /// A request that already fits is booked right away.  The headcount and
+    /// conflict policy of the options apply whenever the request is booked.
+    pub fn join(username: String,
+                bldg_ext_id: Uuid,
+                room_code: String,
+                start_dt: String,
+                end_dt: String,
+                title: String,
+                options: &ScheduleOptions,
+                logger: &Logger,
+                tx: &Transaction)
+                -> Result<WaitlistEntry, MyError> {
+        let time_slot = parse_time_slot(&start_dt, &end_dt, bldg_ext_id, logger, tx)?;
+        let (requester_id, room_id) =
+            Meeting::resolve_booking(&username, bldg_ext_id, &room_code, logger, tx)?;
+        Meeting::check_opening_hours(room_id, &time_slot, logger, tx)?;
+
+        let stmt = "
+		INSERT INTO testing.waitlist_entry(requester_id, room_id, title, time_slot, headcount,
+										   conflict_policy)
+		VALUES ($1, $2, $3, $4, $5, $6)
+		RETURNING testing.waitlist_entry.*, NULL::uuid AS meeting_ext_id;";
+
+        let rows = tx.query(stmt,
+                            &[&requester_id,
+                              &room_id,
+                              &title,
+                              &time_slot,
+                              &options.headcount,
+                              &options.conflict_policy.code()])
+                     .map_err(|err| {
+                         error!(logger, "Failed to join waitlist: DB Error.";
+								"step"=>"join_waitlist", "err"=>err.to_string());
+                         MyError::DBError(DBError::PGError(err))
+                     })?;
+
+        let entry = match rows.iter().next() {
+            Some(row) => WaitlistEntry::from_row(&row),
+            None => {
+                error!(logger, "Error adding waitlist entry to db: No record returned.";
+						"step"=>"join_waitlist");
+                return Err(MyError::DBError(DBError::NoRecord));
+            }
+        };
+        info!(logger, "User {} joined waitlist: {}", username, entry.ext_id);
+
+        let settled = WaitlistEntry::promote(room_id, logger, tx)?;
+        Ok(settled.into_iter()
+                  .find(|settled| settled.id == entry.id)
+                  .unwrap_or(entry))
+    }
+
+    /// The user's waitlist entries, oldest first, so that they can find out
+    /// which of their requests were booked.
+    pub fn get_entries(username: String,
+                       logger: &Logger,
+                       tx: &Transaction)
+                       -> Result<Vec<WaitlistEntry>, MyError> {
+        let stmt = "
+		SELECT w.*, mtg.ext_id AS meeting_ext_id
+		  FROM testing.waitlist_entry w
+		  JOIN testing.users u
+			ON w.requester_id = u.id
+	 LEFT JOIN testing.meeting mtg
+			ON w.meeting_id = mtg.id
+		 WHERE u.username = $1
+	  ORDER BY w.created_at, w.id;";
+
+        tx.query(stmt, &[&username])
+          .map_err(|err| {
+              error!(logger, "Failed to query for waitlist entries: DB Error.";
+					"step"=>"get_entries", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })
+          .map(|rows: Rows| rows.iter().map(|row| WaitlistEntry::from_row(&row)).collect())
+    }
+
+    /// Book the waiting requests for the room that fit now, in the order they
+    /// joined the waitlist.  Requests that fail for reasons other than the
+    /// room being taken, e.g. for exceeding its capacity, are marked as failed
+    /// rather than left waiting forever, as are requests whose time slot has
+    /// already started.  Returns the entries that were booked or failed.
+    fn promote(room_id: i64,
+               logger: &Logger,
+               tx: &Transaction)
+               -> Result<Vec<WaitlistEntry>, MyError> {
+        let stmt = "
+		UPDATE testing.waitlist_entry
+		   SET status = 'failed'
+		 WHERE room_id = $1
+		   AND status = 'waiting'
+		   AND lower(time_slot) <= clock_timestamp()
+		RETURNING testing.waitlist_entry.*, NULL::uuid AS meeting_ext_id;";
+
+        let expired = tx.query(stmt, &[&room_id]).map_err(|err| {
+            error!(logger, "Failed to update waitlist entries: DB Error.";
+					"step"=>"promote", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let mut settled = Vec::new();
+        for entry in expired.iter().map(|row| WaitlistEntry::from_row(&row)) {
+            info!(logger, "Waitlist entry {} expired before it could be booked", entry.ext_id);
+            settled.push(entry);
+        }
+
+        let stmt = "
+		SELECT w.*, NULL::uuid AS meeting_ext_id
+		  FROM testing.waitlist_entry w
+		 WHERE w.room_id = $1
+		   AND w.status = 'waiting'
+		   AND NOT testing.room_blacked_out(w.room_id, testing.buffered_slot(w.room_id, w.time_slot))
+		   AND NOT EXISTS (SELECT true
+							 FROM testing.meeting mtg
+							WHERE mtg.room_id = w.room_id
+							  AND mtg.blocked_slot && testing.buffered_slot(w.room_id,
+																			w.time_slot))
+	  ORDER BY w.created_at, w.id
+		   FOR UPDATE OF w;";
+
+        let rows = tx.query(stmt, &[&room_id]).map_err(|err| {
+            error!(logger, "Failed to query for waitlist entries: DB Error.";
+					"step"=>"promote", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        for entry in rows.iter().map(|row| WaitlistEntry::from_row(&row)) {
+            let new_mtg = NewMeeting { organizer_id: entry.requester_id,
+                                       room_id: entry.room_id,
+                                       title: &entry.title,
+                                       time_slot: entry.time_slot,
+                                       series_id: None,
+                                       occurrence_start: None,
+                                       hold_for: None, };
+            let options = ScheduleOptions { conflict_policy: entry.conflict_policy,
+                                            headcount: entry.headcount,
+                                            ..ScheduleOptions::default() };
+
+            // an earlier request may have taken the slot; such requests keep
+            // waiting
+            let mtg = match Meeting::insert_meeting(&new_mtg, &options, logger, tx) {
+                Ok(mtg) => mtg,
+                Err(MyError::MeetingError(MeetingError::ScheduleConflict)) => continue,
+                Err(MyError::MeetingError(err)) => {
+                    warn!(logger, "Waitlist entry {} cannot be booked: {:?}", entry.ext_id, err;
+						  "step"=>"promote");
+                    settled.push(WaitlistEntry::fail(entry.id, logger, tx)?);
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            let stmt = "
+			UPDATE testing.waitlist_entry w
+			   SET status = 'booked',
+				   meeting_id = mtg.id,
+				   booked_at = clock_timestamp()
+			  FROM testing.meeting mtg
+			 WHERE w.id = $1
+			   AND mtg.id = $2
+			RETURNING w.*, mtg.ext_id AS meeting_ext_id;";
+
+            let rows = tx.query(stmt, &[&entry.id, &mtg.id]).map_err(|err| {
+                error!(logger, "Failed to update waitlist entry: DB Error.";
+						"step"=>"promote", "err"=>err.to_string());
+                MyError::DBError(DBError::PGError(err))
+            })?;
+
+            if let Some(row) = rows.iter().next() {
+                info!(logger, "Booked waitlist entry {} as Meeting: {}", entry.ext_id, mtg.ext_id);
+                settled.push(WaitlistEntry::from_row(&row));
+            }
+        }
+
+        Ok(settled)
+    }
+
+    fn fail(entry_id: i64, logger: &Logger, tx: &Transaction) -> Result<WaitlistEntry, MyError> {
+        let stmt = "
+		UPDATE testing.waitlist_entry
+		   SET status = 'failed'
+		 WHERE id = $1
+		RETURNING testing.waitlist_entry.*, NULL::uuid AS meeting_ext_id;";
+
+        let rows = tx.query(stmt, &[&entry_id]).map_err(|err| {
+            error!(logger, "Failed to update waitlist entry: DB Error.";
+					"step"=>"promote", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => Ok(WaitlistEntry::from_row(&row)),
+            None => {
+                error!(logger, "Error updating waitlist entry in db: No record returned.";
+						"step"=>"promote");
+                Err(MyError::DBError(DBError::NoRecord))
+            }
+        }
+    }
+
+    /// `promote` for each of the rooms of the released meetings.
+    fn promote_rooms(released: &[Meeting],
+                     logger: &Logger,
+                     tx: &Transaction)
+                     -> Result<(), MyError> {
+        let mut room_ids = released.iter().map(|mtg| mtg.room_id).collect::<Vec<i64>>();
+        room_ids.sort();
+        room_ids.dedup();
+
+        for room_id in room_ids {
+            WaitlistEntry::promote(room_id, logger, tx)?;
+        }
+
+        Ok(())
+    }
+
+    fn from_row(row: &Row) -> WaitlistEntry {
+        let status: String = row.get("status");
+        let conflict_policy: String = row.get("conflict_policy");
+
+        WaitlistEntry { id: row.get("id"),
+                        ext_id: row.get("ext_id"),
+                        requester_id: row.get("requester_id"),
+                        room_id: row.get("room_id"),
+                        title: row.get("title"),
+                        time_slot: row.get("time_slot"),
+                        headcount: row.get("headcount"),
+                        conflict_policy: ConflictPolicy::from_code(&conflict_policy)
+                                         .expect("constrained by db check"),
+                        status: WaitlistStatus::from_code(&status)
+                                .expect("constrained by db check"),
+                        meeting_ext_id: row.get("meeting_ext_id"), }
+    }
+}
+
+#[derive(Debug)]
+pub struct MeetingSeries {
+    pub id: i64,
+    pub ext_id: Uuid,
+    pub organizer_id: i64,
+    pub room_id: i64,
+    pub title: String,
+    pub first_slot: TSTZRange,
+    pub recurrence: Recurrence,
+}
+
+/// Outcome of booking one occurrence of a meeting series.
+#[derive(Debug)]
+pub struct SeriesOccurrence {
+    pub time_slot: TSTZRange,
+    pub result: Result<Meeting, MeetingError>,
+}
+
+/// The new definition of a series for `MeetingSeries::update_from`, with the
+/// same arguments as `MeetingSeries::schedule_series`.
+#[derive(Debug, Clone)]
+pub struct SeriesUpdate {
+    pub bldg_ext_id: Uuid,
+    pub room_code: String,
+    pub start_dt: String,
+    pub end_dt: String,
+    pub title: String,
+    pub recurrence: Recurrence,
+}
+
+impl MeetingSeries {
+    /// Create a recurring meeting and book every occurrence of it.
+    ///
+    /// Occurrences that cannot be booked, e.g. because of a scheduling
+    /// conflict, are reported individually rather than aborting the series.
+    pub fn schedule_series(username: String,
+                           bldg_ext_id: Uuid,
+                           room_code: String,
+                           start_dt: String,
+                           end_dt: String,
+                           title: String,
+                           recurrence: Recurrence,
+                           logger: &Logger,
+                           tx: &Transaction)
+                           -> Result<(MeetingSeries, Vec<SeriesOccurrence>), MyError> {
+        recurrence.validate()?;
+        let (organizer_id, room_id) =
+            Meeting::resolve_booking(&username, bldg_ext_id, &room_code, logger, tx)?;
+        let first_slot = parse_time_slot(&start_dt, &end_dt, bldg_ext_id, logger, tx)?;
+
+        let series = MeetingSeries::insert_series(organizer_id,
+                                                  room_id,
+                                                  &title,
+                                                  &first_slot,
+                                                  &recurrence,
+                                                  logger,
+                                                  tx)?;
+
+        let occurrences = series.book_occurrences(logger, tx)?;
+        info!(logger, "Scheduled Meeting Series: {}", series.ext_id;
+              "occurrences"=>occurrences.len());
+
+        Ok((series, occurrences))
+    }
+
+    /// Cancel a single occurrence of the series, e.g. for a holiday.
+    pub fn cancel_occurrence(series_ext_id: Uuid,
+                             occurrence_start: String,
+                             acting_username: String,
+                             logger: &Logger,
+                             tx: &Transaction)
+                             -> Result<Meeting, MyError> {
+        let mtg_ext_id =
+            MeetingSeries::find_occurrence(series_ext_id, &occurrence_start, logger, tx)?;
+        Meeting::cancel_meeting(mtg_ext_id, acting_username, logger, tx)
+    }
+
+    /// Move a single occurrence of the series to another time slot and/or room,
+    /// per `Meeting::reschedule`.
+    pub fn move_occurrence(series_ext_id: Uuid,
+                           occurrence_start: String,
+                           new_room_code: String,
+                           new_bldg_ext_id: Uuid,
+                           new_start: String,
+                           new_end: String,
+                           acting_username: String,
+                           options: &ScheduleOptions,
+                           logger: &Logger,
+                           tx: &Transaction)
+                           -> Result<Meeting, MyError> {
+        let mtg_ext_id =
+            MeetingSeries::find_occurrence(series_ext_id, &occurrence_start, logger, tx)?;
+        Meeting::reschedule(mtg_ext_id,
+                            acting_username,
+                            new_room_code,
+                            new_bldg_ext_id,
+                            new_start,
+                            new_end,
+                            options,
+                            logger,
+                            tx)
+    }
+
+    /// Change the series from `from_dt` forward.
+    ///
+    /// The existing series is ended just before `from_dt` and its generated
+    /// occurrences from then on are released.  A new series, starting at
+    /// `update.start_dt`, replaces it and takes over the later exceptions:
+    /// individually modified occurrences are kept and cancelled ones are not
+    /// booked again.  The attendees of the released occurrences are invited to
+    /// the new ones, keeping their response where the start time is unchanged.
+    pub fn update_from(series_ext_id: Uuid,
+                       from_dt: String,
+                       update: &SeriesUpdate,
+                       acting_username: String,
+                       logger: &Logger,
+                       tx: &Transaction)
+                       -> Result<(MeetingSeries, Vec<SeriesOccurrence>), MyError> {
+        let bldg_ext_id = update.bldg_ext_id;
+        let from_dt = parse_timestamp(&from_dt, "from_dt", bldg_ext_id, logger, tx)?;
+        if parse_timestamp(&update.start_dt, "start_dt", bldg_ext_id, logger, tx)? < from_dt {
+            info!(logger, "Updated series may not start before from_dt");
+            return Err(MyError::ValueError(None));
+        }
+
+        let stmt = "
+		SELECT s.id,
+			   org.username,
+			   org.username = $2 OR COALESCE(actor.is_admin, false)
+		  FROM testing.meeting_series s
+		  JOIN testing.users org
+			ON s.organizer_id = org.id
+	 LEFT JOIN testing.users actor
+			ON actor.username = $2
+		 WHERE s.ext_id = $1
+		   FOR UPDATE OF s;";
+
+        let rows = tx.query(stmt, &[&series_ext_id, &acting_username]).map_err(|err| {
+            error!(logger, "Failed to look up meeting series: DB Error.";
+					"step"=>"update_from", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let (series_id, organizer): (i64, String) = match rows.iter().next() {
+            Some(row) => {
+                let permitted: bool = row.get(2);
+                if !permitted {
+                    info!(logger, "User {} may not modify meeting series {}",
+                          acting_username, series_ext_id);
+                    return Err(MyError::MeetingError(MeetingError::NotPermitted));
+                }
+                (row.get(0), row.get(1))
+            }
+            None => {
+                info!(logger, "No such meeting series: {}", series_ext_id);
+                return Err(MyError::MeetingError(MeetingError::NotFound));
+            }
+        };
+
+        update.recurrence.validate()?;
+        let (organizer_id, room_id) =
+            Meeting::resolve_booking(&organizer, bldg_ext_id, &update.room_code, logger, tx)?;
+        let first_slot =
+            parse_time_slot(&update.start_dt, &update.end_dt, bldg_ext_id, logger, tx)?;
+
+        let stmt = "
+		SELECT mtg.occurrence_start, att.user_id, att.is_required, att.status
+		  FROM testing.meeting mtg
+		  JOIN testing.meeting_attendee att
+			ON att.meeting_id = mtg.id
+		 WHERE mtg.series_id = $1
+		   AND NOT mtg.is_modified
+		   AND mtg.occurrence_start >= $2;";
+
+        let rows = tx.query(stmt, &[&series_id, &from_dt]).map_err(|err| {
+            error!(logger, "Failed to look up series attendees: DB Error.";
+					"step"=>"update_from", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+        let attendee_starts = rows.iter().map(|row| row.get(0)).collect::<Vec<DateTime<Utc>>>();
+        let attendee_ids = rows.iter().map(|row| row.get(1)).collect::<Vec<i64>>();
+        let attendee_required = rows.iter().map(|row| row.get(2)).collect::<Vec<bool>>();
+        let attendee_status = rows.iter().map(|row| row.get(3)).collect::<Vec<String>>();
+
+        let stmt = "
+		WITH released AS (
+			DELETE FROM testing.meeting
+			 WHERE series_id = $1
+			   AND NOT is_modified
+			   AND occurrence_start >= $2
+			RETURNING room_id
+		), ended AS (
+			UPDATE testing.meeting_series
+			   SET until_dt = $2 - interval '1 microsecond'
+			 WHERE id = $1
+		)
+		SELECT DISTINCT room_id FROM released;";
+
+        let rows = tx.query(stmt, &[&series_id, &from_dt]).map_err(|err| {
+            error!(logger, "Failed to end meeting series: DB Error.";
+					"step"=>"update_from", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+        let released_room_ids = rows.iter().map(|row| row.get(0)).collect::<Vec<i64>>();
+
+        info!(logger, "Ended Meeting Series: {}", series_ext_id; "from"=>from_dt.to_rfc3339());
+
+        let series = MeetingSeries::insert_series(organizer_id,
+                                                  room_id,
+                                                  &update.title,
+                                                  &first_slot,
+                                                  &update.recurrence,
+                                                  logger,
+                                                  tx)?;
+
+        // the kept and cancelled occurrences from then on belong to the new
+        // series, so that booking it does not bring them back
+        let stmt = "
+		WITH exceptions AS (
+			UPDATE testing.meeting_series_exception
+			   SET series_id = $2
+			 WHERE series_id = $1
+			   AND occurrence_start >= $3
+		)
+		UPDATE testing.meeting
+		   SET series_id = $2
+		 WHERE series_id = $1
+		   AND occurrence_start >= $3;";
+
+        tx.execute(stmt, &[&series_id, &series.id, &from_dt]).map_err(|err| {
+            error!(logger, "Failed to carry over series exceptions: DB Error.";
+					"step"=>"update_from", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let occurrences = series.book_occurrences(logger, tx)?;
+        let booked_ids = occurrences.iter()
+                                    .filter_map(|occ| occ.result.as_ref().ok())
+                                    .map(|mtg| mtg.id)
+                                    .collect::<Vec<i64>>();
+
+        let stmt = "
+		INSERT INTO testing.meeting_attendee(meeting_id, user_id, is_required, status)
+		SELECT DISTINCT ON (mtg.id, att.user_id)
+			   mtg.id,
+			   att.user_id,
+			   att.is_required,
+			   CASE WHEN att.occurrence_start = mtg.occurrence_start THEN att.status
+					ELSE 'invited' END
+		  FROM testing.meeting mtg,
+			   unnest($2::timestamptz[], $3::bigint[], $4::boolean[], $5::text[])
+				   AS att(occurrence_start, user_id, is_required, status)
+		 WHERE mtg.id = ANY($1)
+	  ORDER BY mtg.id,
+			   att.user_id,
+			   att.occurrence_start = mtg.occurrence_start DESC,
+			   att.is_required DESC;";
+
+        tx.execute(stmt,
+                   &[&booked_ids,
+                     &attendee_starts,
+                     &attendee_ids,
+                     &attendee_required,
+                     &attendee_status])
+          .map_err(|err| {
+              error!(logger, "Failed to carry over series attendees: DB Error.";
+					"step"=>"update_from", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })?;
+
+        info!(logger, "Scheduled Meeting Series: {}", series.ext_id;
+              "occurrences"=>occurrences.len());
+
+        // the updated series gets first pick of the released time
+        for room_id in released_room_ids {
+            WaitlistEntry::promote(room_id, logger, tx)?;
+        }
+
+        Ok((series, occurrences))
+    }
+
+    /// Occurrences of the series that are currently booked, ordered by start.
+    /// Use `Meeting::occurrence_kind` to tell generated and modified ones apart.
+    pub fn get_occurrences(series_ext_id: Uuid,
+                           logger: &Logger,
+                           tx: &Transaction)
+                           -> Result<Vec<Meeting>, MyError> {
+        let stmt = "
+		SELECT mtg.*
+		  FROM testing.meeting mtg
+		  JOIN testing.meeting_series s
+			ON mtg.series_id = s.id
+		 WHERE s.ext_id = $1
+	  ORDER BY lower(mtg.time_slot);";
+
+        tx.query(stmt, &[&series_ext_id])
+          .map_err(|err| {
+              error!(logger, "Failed to query for series occurrences: DB Error.";
+					"step"=>"get_occurrences", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })
+          .and_then(|rows: Rows| rows.iter().map(|row| Meeting::from_row(&row)).collect())
+    }
+
+    /// Original start times of the occurrences that were cancelled.
+    pub fn get_cancelled_occurrences(series_ext_id: Uuid,
+                                     logger: &Logger,
+                                     tx: &Transaction)
+                                     -> Result<Vec<DateTime<Utc>>, MyError> {
+        let stmt = "
+		SELECT ex.occurrence_start
+		  FROM testing.meeting_series_exception ex
+		  JOIN testing.meeting_series s
+			ON ex.series_id = s.id
+		 WHERE s.ext_id = $1
+	  ORDER BY ex.occurrence_start;";
+
+        tx.query(stmt, &[&series_ext_id])
+          .map_err(|err| {
+              error!(logger, "Failed to query for cancelled occurrences: DB Error.";
+					"step"=>"get_cancelled_occurrences", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })
+          .map(|rows: Rows| rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    /// The ext_id of the meeting booked for the occurrence originally starting
+    /// at `occurrence_start`, which may be given in the series' local time.
+    fn find_occurrence(series_ext_id: Uuid,
+                       occurrence_start: &str,
+                       logger: &Logger,
+                       tx: &Transaction)
+                       -> Result<Uuid, MyError> {
+        let stmt = "
+		SELECT b.ext_id
+		  FROM testing.meeting_series s
+		  JOIN testing.room r
+			ON s.room_id = r.id
+		  JOIN testing.building b
+			ON r.building_id = b.id
+		 WHERE s.ext_id = $1;";
+
+        let rows = tx.query(stmt, &[&series_ext_id]).map_err(|err| {
+            error!(logger, "Failed to look up meeting series: DB Error.";
+					"step"=>"find_occurrence", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let bldg_ext_id: Uuid = match rows.iter().next() {
+            Some(row) => row.get(0),
+            None => {
+                info!(logger, "No such meeting series: {}", series_ext_id);
+                return Err(MyError::MeetingError(MeetingError::NotFound));
+            }
+        };
+
+        let occurrence_start =
+            parse_timestamp(occurrence_start, "occurrence_start", bldg_ext_id, logger, tx)?;
+
+        let stmt = "
+		SELECT mtg.ext_id
+		  FROM testing.meeting mtg
+		  JOIN testing.meeting_series s
+			ON mtg.series_id = s.id
+		 WHERE s.ext_id = $1
+		   AND mtg.occurrence_start = $2;";
+
+        let rows = tx.query(stmt, &[&series_ext_id, &occurrence_start]).map_err(|err| {
+            error!(logger, "Failed to look up series occurrence: DB Error.";
+					"step"=>"find_occurrence", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        match rows.iter().next() {
+            Some(row) => Ok(row.get(0)),
+            None => {
+                info!(logger, "No occurrence of series {} at {}",
+                      series_ext_id, occurrence_start);
+                Err(MyError::MeetingError(MeetingError::NotFound))
+            }
+        }
+    }
+
+    fn insert_series(organizer_id: i64,
+                     room_id: i64,
+                     title: &str,
+                     first_slot: &TSTZRange,
+                     recurrence: &Recurrence,
+                     logger: &Logger,
+                     tx: &Transaction)
+                     -> Result<MeetingSeries, MyError> {
+        let frequency = recurrence.frequency.code();
+        let interval = recurrence.interval as i32;
+        let by_weekday = recurrence.by_weekday
+                                   .iter()
+                                   .map(|day| day.num_days_from_monday() as i32)
+                                   .collect::<Vec<i32>>();
+        let count = recurrence.count.map(|count| count as i32);
+
+        let stmt = "
+		INSERT INTO testing.meeting_series(organizer_id, room_id, title, first_slot,
+										   frequency, freq_interval, by_weekday,
+										   until_dt, occurrence_count)
+		VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+		RETURNING *;";
+
+        let rows = tx.query(stmt,
+                            &[&organizer_id,
+                              &room_id,
+                              &title,
+                              &first_slot,
+                              &frequency,
+                              &interval,
+                              &by_weekday,
+                              &recurrence.until,
+                              &count])
+                     .map_err(|err| {
+                         error!(logger, "Failed to create meeting series: DB Error.";
+								"step"=>"insert_series", "err"=>err.to_string());
+                         MyError::DBError(DBError::PGError(err))
+                     })?;
+
+        let series = match rows.iter().next() {
+            Some(row) => MeetingSeries::from_row(&row),
+            None => {
+                error!(logger, "Error creating meeting series in db: No record returned.";
+						"step"=>"insert_series");
+                return Err(MyError::DBError(DBError::NoRecord));
+            }
+        };
+
+        Ok(series)
+    }
+
+    /// Book the occurrences of the series that are neither booked already nor
+    /// cancelled.
+    fn book_occurrences(&self,
+                        logger: &Logger,
+                        tx: &Transaction)
+                        -> Result<Vec<SeriesOccurrence>, MyError> {
+        let first_start = self.first_slot.lower().unwrap().value;
+        let duration = self.first_slot.upper().unwrap().value - first_start;
+
+        let stmt = "
+		SELECT occurrence_start
+		  FROM testing.meeting_series_exception
+		 WHERE series_id = $1
+		 UNION
+		SELECT occurrence_start
+		  FROM testing.meeting
+		 WHERE series_id = $1;";
+
+        let rows = tx.query(stmt, &[&self.id]).map_err(|err| {
+            error!(logger, "Failed to look up series exceptions: DB Error.";
+					"step"=>"book_occurrences", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+        let exceptions = rows.iter()
+                             .map(|row| row.get(0))
+                             .collect::<Vec<DateTime<Utc>>>();
+
+        let mut occurrences = Vec::new();
+        for start in self.local_occurrences(logger, tx)? {
+            if exceptions.contains(&start) {
+                continue;
+            }
+            let time_slot = timeslots::slot(start, start + duration);
+
+            let new_mtg = NewMeeting { organizer_id: self.organizer_id,
+                                       room_id: self.room_id,
+                                       title: &self.title,
+                                       time_slot,
+                                       series_id: Some(self.id),
+                                       occurrence_start: Some(start),
+                                       hold_for: None, };
+
+            let result = match Meeting::insert_meeting(&new_mtg,
+                                                       &ScheduleOptions::default(),
+                                                       logger,
+                                                       tx) {
+                Ok(mtg) => Ok(mtg),
+                Err(MyError::MeetingError(err)) => Err(err),
+                Err(err) => return Err(err),
+            };
+
+            occurrences.push(SeriesOccurrence { time_slot, result });
+        }
+
+        Ok(occurrences)
+    }
+
+    /// Start times of the series' occurrences.  The recurrence rule is applied
+    /// to the building's wall clock, so that e.g. a weekly 9:00 meeting stays
+    /// at 9:00 local time across DST changes.
+    fn local_occurrences(&self,
+                         logger: &Logger,
+                         tx: &Transaction)
+                         -> Result<Vec<DateTime<Utc>>, MyError> {
+        let stmt = "
+		SELECT lower(s.first_slot) AT TIME ZONE b.timezone,
+			   s.until_dt AT TIME ZONE b.timezone,
+			   b.timezone
+		  FROM testing.meeting_series s
+		  JOIN testing.room r
+			ON s.room_id = r.id
+		  JOIN testing.building b
+			ON r.building_id = b.id
+		 WHERE s.id = $1;";
+
+        let rows = tx.query(stmt, &[&self.id]).map_err(|err| {
+            error!(logger, "Failed to look up series timezone: DB Error.";
+					"step"=>"local_occurrences", "err"=>err.to_string());
+            MyError::DBError(DBError::PGError(err))
+        })?;
+
+        let (local_first_start, local_until, timezone): (NaiveDateTime,
+                                                         Option<NaiveDateTime>,
+                                                         String) =
+            match rows.iter().next() {
+                Some(row) => (row.get(0), row.get(1), row.get(2)),
+                None => return Err(MyError::DBError(DBError::NoRecord)),
+            };
+
+        // the rule is expanded on wall-clock times posing as UTC
+        let mut recurrence = self.recurrence.clone();
+        recurrence.until = local_until.map(|until| Utc.from_utc_datetime(&until));
+        let local_starts = recurrence.occurrences(Utc.from_utc_datetime(&local_first_start))
+                                     .iter()
+                                     .map(|start| start.naive_utc())
+                                     .collect::<Vec<NaiveDateTime>>();
+
+        let stmt = "
+		SELECT local.start AT TIME ZONE $2
+		  FROM unnest($1::timestamp[]) WITH ORDINALITY local(start, n)
+	  ORDER BY local.n;";
+
+        tx.query(stmt, &[&local_starts, &timezone])
+          .map_err(|err| {
+              error!(logger, "Failed to convert occurrence start times: DB Error.";
+					"step"=>"local_occurrences", "err"=>err.to_string());
+              MyError::DBError(DBError::PGError(err))
+          })
+          .map(|rows: Rows| rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    fn from_row(row: &Row) -> MeetingSeries {
+        let frequency: String = row.get("frequency");
+        let interval: i32 = row.get("freq_interval");
+        let by_weekday: Vec<i32> = row.get("by_weekday");
+        let count: Option<i32> = row.get("occurrence_count");
+
+        let recurrence = Recurrence { frequency: Frequency::from_code(&frequency)
+                                                 .expect("constrained by db check"),
+                                      interval: interval as u32,
+                                      by_weekday: by_weekday.into_iter()
+                                                            .map(weekday_from_num)
+                                                            .collect(),
+                                      until: row.get("until_dt"),
+                                      count: count.map(|count| count as u32), };
+
+        MeetingSeries { id: row.get("id"),
+                        ext_id: row.get("ext_id"),
+                        organizer_id: row.get("organizer_id"),
+                        room_id: row.get("room_id"),
+                        title: row.get("title"),
+                        first_slot: row.get("first_slot"),
+                        recurrence, }
+    }
+}
+
+/// Inverse of `Weekday::num_days_from_monday`, as stored in the db.
+fn weekday_from_num(num: i32) -> Weekday {
+    match num {
+        0 => Weekday::Mon,
+        1 => Weekday::Tue,
+        2 => Weekday::Wed,
+        3 => Weekday::Thu,
+        4 => Weekday::Fri,
+        5 => Weekday::Sat,
+        _ => Weekday::Sun,
     }
 }
@@ -0,0 +1,137 @@
+/*
+This module expands RRULE-style recurrence rules (frequency, interval,
+by-weekday, until/count) into the start times of individual occurrences.
+*/
+use chrono::prelude::*;
+use chrono::Duration;
+
+use errors::MyError;
+
+/// Upper bound on the occurrences generated from a single rule, so that a rule
+/// without an `until` or a `count` cannot book rooms indefinitely.  A rule
+/// asking for more occurrences than this is rejected, see `validate`.
+pub const MAX_OCCURRENCES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+impl Frequency {
+    pub fn code(&self) -> &'static str {
+        match *self {
+            Frequency::Daily => "daily",
+            Frequency::Weekly => "weekly",
+            Frequency::Monthly => "monthly",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Frequency> {
+        match code {
+            "daily" => Some(Frequency::Daily),
+            "weekly" => Some(Frequency::Weekly),
+            "monthly" => Some(Frequency::Monthly),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub by_weekday: Vec<Weekday>,
+    pub until: Option<DateTime<Utc>>,
+    pub count: Option<u32>,
+}
+impl Recurrence {
+    pub fn new(frequency: Frequency) -> Recurrence {
+        Recurrence { frequency,
+                     interval: 1,
+                     by_weekday: Vec::new(),
+                     until: None,
+                     count: None, }
+    }
+
+    /// Rejects a `count` above `MAX_OCCURRENCES` rather than booking only part
+    /// of the series.
+    pub fn validate(&self) -> Result<(), MyError> {
+        match self.count {
+            Some(count) if count as usize > MAX_OCCURRENCES => Err(MyError::ValueError(None)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Start times of every occurrence of the rule, beginning at `first_start`.
+    ///
+    /// As with RRULE, `by_weekday` expands a weekly rule to several days of the
+    /// week and filters the days generated by a daily or monthly rule.
+    pub fn occurrences(&self, first_start: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let limit = self.count
+                        .map(|count| count as usize)
+                        .unwrap_or(MAX_OCCURRENCES);
+        let interval = self.interval.max(1) as i64;
+
+        let mut weekdays = self.by_weekday.clone();
+        weekdays.sort_by_key(|day| day.num_days_from_monday());
+        weekdays.dedup();
+
+        let mut starts = Vec::new();
+
+        // a filtering rule may never match (e.g. the 31st of each month that
+        // falls on a Monday), so the number of periods examined is bounded too
+        for period in 0..(MAX_OCCURRENCES as i64 * 10) {
+            let candidates = match self.frequency {
+                Frequency::Daily => vec![first_start + Duration::days(period * interval)],
+                Frequency::Weekly => {
+                    let days = if weekdays.is_empty() {
+                        vec![first_start.weekday()]
+                    } else {
+                        weekdays.clone()
+                    };
+                    let week_start = first_start
+                                     - Duration::days(first_start.weekday()
+                                                                 .num_days_from_monday()
+                                                      as i64)
+                                     + Duration::weeks(period * interval);
+
+                    days.iter()
+                        .map(|day| week_start + Duration::days(day.num_days_from_monday() as i64))
+                        .filter(|start| *start >= first_start)
+                        .collect()
+                }
+                Frequency::Monthly => add_months(first_start, period * interval).into_iter()
+                                                                               .collect(),
+            };
+
+            for start in candidates {
+                if self.until.is_some_and(|until| start > until) || starts.len() >= limit {
+                    return starts;
+                }
+
+                let matches_weekday = self.frequency == Frequency::Weekly
+                                      || weekdays.is_empty()
+                                      || weekdays.contains(&start.weekday());
+                if matches_weekday {
+                    starts.push(start);
+                }
+            }
+        }
+
+        starts
+    }
+}
+
+/// Same day-of-month and time, `months` later.  Returns None when the target
+/// month is too short (e.g. February 30th).
+fn add_months(dt: DateTime<Utc>, months: i64) -> Option<DateTime<Utc>> {
+    let total = dt.year() as i64 * 12 + dt.month0() as i64 + months;
+    let year = (total / 12) as i32;
+    let month0 = (total % 12) as u32;
+
+    dt.with_day(1)?
+      .with_year(year)?
+      .with_month0(month0)?
+      .with_day(dt.day())
+}
@@ -11,6 +11,7 @@ CREATE TABLE testing.users (
 	first_name  VARCHAR(200) NOT NULL,
 	last_name   VARCHAR(200) NOT NULL,
 	username    VARCHAR(50) UNIQUE NOT NULL,
+	is_admin    BOOLEAN NOT NULL DEFAULT false,
 	UNIQUE (first_name, last_name)
 );
 
@@ -18,7 +19,102 @@ CREATE TABLE testing.users (
 CREATE TABLE testing.building (
 	id  BIGSERIAL PRIMARY KEY,
 	ext_id UUID NOT NULL DEFAULT  uuid_generate_v4() UNIQUE,
-	name   VARCHAR(200) UNIQUE
+	name   VARCHAR(200) UNIQUE,
+	checkin_grace_minutes   INTEGER NOT NULL DEFAULT 15 CHECK (checkin_grace_minutes > 0),
+	timezone   VARCHAR(64) NOT NULL DEFAULT 'UTC'
+);
+
+
+-- Weekly opening hours; a building without any is open around the clock.
+-- Weekdays are numbered from Monday = 0.
+CREATE TABLE testing.building_hours (
+	id  BIGSERIAL PRIMARY KEY,
+	building_id   BIGINT REFERENCES testing.building(id) ON DELETE CASCADE NOT NULL,
+	weekday   INTEGER NOT NULL CHECK (weekday BETWEEN 0 AND 6),
+	open_time   TIME NOT NULL,
+	close_time   TIME NOT NULL,
+	CHECK (open_time < close_time),
+	UNIQUE(building_id, weekday)
+);
+
+
+CREATE TABLE testing.building_closure (
+	id  BIGSERIAL PRIMARY KEY,
+	building_id   BIGINT REFERENCES testing.building(id) ON DELETE CASCADE NOT NULL,
+	closed_on   DATE NOT NULL,
+	description   VARCHAR(200) NOT NULL,
+	UNIQUE(building_id, closed_on)
+);
+
+
+-- The parts of the window during which the building is closed: its closure
+-- days and, if it has opening hours, the time outside of them.  Opening hours
+-- and closure days follow the building's local time.
+CREATE FUNCTION testing.closed_periods(building_id BIGINT, time_window TSTZRANGE)
+RETURNS SETOF TSTZRANGE AS $$
+	WITH days AS (
+		SELECT d::date AS day,
+			   extract(isodow FROM d) - 1 AS weekday,
+			   b.timezone
+		  FROM testing.building b,
+			   generate_series((lower($2) AT TIME ZONE b.timezone)::date,
+							   (upper($2) AT TIME ZONE b.timezone)::date,
+							   interval '1 day') d
+		 WHERE b.id = $1
+	), closed AS (
+		SELECT days.day + time '00:00' AS closed_from,
+			   days.day + 1 + time '00:00' AS closed_until,
+			   days.timezone
+		  FROM days
+		  JOIN testing.building_closure c
+			ON c.building_id = $1
+		   AND c.closed_on = days.day
+		UNION ALL
+		SELECT days.day + time '00:00',
+			   COALESCE(days.day + h.open_time, days.day + 1 + time '00:00'),
+			   days.timezone
+		  FROM days
+	 LEFT JOIN testing.building_hours h
+			ON h.building_id = $1
+		   AND h.weekday = days.weekday
+		 WHERE EXISTS (SELECT true
+						 FROM testing.building_hours
+						WHERE building_hours.building_id = $1)
+		UNION ALL
+		SELECT days.day + h.close_time,
+			   days.day + 1 + time '00:00',
+			   days.timezone
+		  FROM days
+		  JOIN testing.building_hours h
+			ON h.building_id = $1
+		   AND h.weekday = days.weekday
+	)
+	SELECT period
+	  FROM (SELECT tstzrange(closed_from AT TIME ZONE timezone,
+							 closed_until AT TIME ZONE timezone, '[)') * $2 AS period
+			  FROM closed) periods
+	 WHERE NOT isempty(period);
+$$ LANGUAGE sql STABLE;
+
+
+-- Whether the building is open for the entire time slot.
+CREATE FUNCTION testing.within_opening_hours(building_id BIGINT, time_slot TSTZRANGE)
+RETURNS BOOLEAN AS $$
+	SELECT NOT EXISTS (SELECT true
+						 FROM testing.closed_periods($1, $2));
+$$ LANGUAGE sql STABLE;
+
+
+-- Kinds of room, e.g. phone booths or auditoriums, each with its own booking
+-- rules.  A NULL limit means there is none.
+CREATE TABLE testing.room_type (
+	id  BIGSERIAL PRIMARY KEY,
+	ext_id UUID NOT NULL DEFAULT  uuid_generate_v4() UNIQUE,
+	code  VARCHAR(30) NOT NULL UNIQUE,
+	name  VARCHAR(100) NOT NULL,
+	max_booking_minutes   INTEGER CHECK (max_booking_minutes > 0),
+	advance_booking_days   INTEGER CHECK (advance_booking_days > 0),
+	requires_approval   BOOLEAN NOT NULL DEFAULT false
 );
 
 
@@ -28,10 +124,70 @@ CREATE TABLE testing.room (
 	building_id   BIGINT REFERENCES testing.building(id) NOT NULL,
 	code  VARCHAR(10) NOT NULL,
 	floor_num   INTEGER NOT NULL,
+	capacity   INTEGER NOT NULL CHECK (capacity > 0),
+	setup_minutes   INTEGER NOT NULL DEFAULT 0 CHECK (setup_minutes >= 0),
+	cleanup_minutes   INTEGER NOT NULL DEFAULT 0 CHECK (cleanup_minutes >= 0),
+	room_type_id   BIGINT REFERENCES testing.room_type(id),
 	UNIQUE(building_id, code) 
 );
 
 
+CREATE TABLE testing.amenity (
+	id  BIGSERIAL PRIMARY KEY,
+	ext_id UUID NOT NULL DEFAULT  uuid_generate_v4() UNIQUE,
+	code  VARCHAR(30) NOT NULL UNIQUE,
+	name  VARCHAR(100) NOT NULL
+);
+
+
+CREATE TABLE testing.room_amenity (
+	id  BIGSERIAL PRIMARY KEY,
+	room_id  BIGINT REFERENCES testing.room(id) ON DELETE CASCADE NOT NULL,
+	amenity_id  BIGINT REFERENCES testing.amenity(id) ON DELETE CASCADE NOT NULL,
+	UNIQUE(room_id, amenity_id)
+);
+
+
+-- The time a meeting keeps the room occupied: its time slot plus the room's
+-- setup and cleanup buffers.
+CREATE FUNCTION testing.buffered_slot(room_id BIGINT, time_slot TSTZRANGE)
+RETURNS TSTZRANGE AS $$
+	SELECT tstzrange(lower($2) - r.setup_minutes * interval '1 minute',
+					 upper($2) + r.cleanup_minutes * interval '1 minute',
+					 CASE WHEN lower_inc($2) THEN '[' ELSE '(' END
+					 || CASE WHEN upper_inc($2) THEN ']' ELSE ')' END)
+	  FROM testing.room r
+	 WHERE r.id = $1;
+$$ LANGUAGE sql STABLE;
+
+
+-- Whether bookings of the room need an administrator's approval, per its type.
+CREATE FUNCTION testing.requires_approval(room_id BIGINT)
+RETURNS BOOLEAN AS $$
+	SELECT COALESCE((SELECT t.requires_approval
+					   FROM testing.room r
+					   JOIN testing.room_type t
+						 ON r.room_type_id = t.id
+					  WHERE r.id = $1), false);
+$$ LANGUAGE sql STABLE;
+
+
+CREATE TABLE testing.meeting_series (
+	id  BIGSERIAL PRIMARY KEY,
+	ext_id UUID NOT NULL DEFAULT  uuid_generate_v4() UNIQUE,
+	organizer_id BIGINT REFERENCES testing.users(id) NOT NULL,
+	room_id  BIGINT REFERENCES testing.room(id) NOT NULL,
+	title   VARCHAR(200) NOT NULL,
+	first_slot   TSTZRANGE NOT NULL,
+	frequency   VARCHAR(10) NOT NULL CHECK (frequency IN ('daily', 'weekly', 'monthly')),
+	freq_interval   INTEGER NOT NULL DEFAULT 1 CHECK (freq_interval > 0),
+	by_weekday   INTEGER[] NOT NULL DEFAULT '{}',
+	until_dt   TIMESTAMPTZ,
+	occurrence_count   INTEGER CHECK (occurrence_count > 0),
+	CONSTRAINT series_first_slot_half_open CHECK (lower_inc(first_slot) AND NOT upper_inc(first_slot))
+);
+
+
 CREATE TABLE testing.meeting (
 	id  BIGSERIAL PRIMARY KEY,
 	ext_id UUID NOT NULL DEFAULT  uuid_generate_v4() UNIQUE,
@@ -39,5 +195,152 @@ CREATE TABLE testing.meeting (
 	room_id  BIGINT REFERENCES testing.room(id) NOT NULL,
 	title   VARCHAR(200) NOT NULL,
 	time_slot   TSTZRANGE NOT NULL,
-	CONSTRAINT mtg_timeslot_overlap EXCLUDE USING gist (room_id WITH =, time_slot WITH &&)
+	series_id   BIGINT REFERENCES testing.meeting_series(id),
+	occurrence_start   TIMESTAMPTZ,
+	is_modified   BOOLEAN NOT NULL DEFAULT false,
+	headcount   INTEGER CHECK (headcount > 0),
+	status   VARCHAR(10) NOT NULL DEFAULT 'confirmed'
+			 CHECK (status IN ('hold', 'pending', 'confirmed')),
+	hold_expires_at   TIMESTAMPTZ,
+	priority   SMALLINT NOT NULL DEFAULT 0 CHECK (priority BETWEEN 0 AND 2),
+	checked_in_at   TIMESTAMPTZ,
+	released_at   TIMESTAMPTZ,
+	blocked_slot   TSTZRANGE NOT NULL,
+	local_start   TIMESTAMP NOT NULL,
+	local_end   TIMESTAMP NOT NULL,
+	CHECK ((status = 'hold') = (hold_expires_at IS NOT NULL)),
+	CONSTRAINT mtg_timeslot_half_open CHECK (lower_inc(time_slot) AND NOT upper_inc(time_slot)),
+	CONSTRAINT mtg_timeslot_overlap EXCLUDE USING gist (room_id WITH =, blocked_slot WITH &&)
+);
+
+
+-- Derives the time the room is blocked, and the meeting's wall-clock times in
+-- the building's timezone, from the time slot.
+CREATE FUNCTION testing.set_derived_slots() RETURNS trigger AS $$
+BEGIN
+	SELECT testing.buffered_slot(NEW.room_id, NEW.time_slot),
+		   lower(NEW.time_slot) AT TIME ZONE b.timezone,
+		   upper(NEW.time_slot) AT TIME ZONE b.timezone
+	  INTO NEW.blocked_slot, NEW.local_start, NEW.local_end
+	  FROM testing.room r
+	  JOIN testing.building b
+		ON r.building_id = b.id
+	 WHERE r.id = NEW.room_id;
+	RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER mtg_derived_slots
+	BEFORE INSERT OR UPDATE OF room_id, time_slot ON testing.meeting
+	FOR EACH ROW EXECUTE PROCEDURE testing.set_derived_slots();
+
+
+-- Times a room cannot be booked at all, e.g. during repairs.
+CREATE TABLE testing.room_blackout (
+	id  BIGSERIAL PRIMARY KEY,
+	ext_id UUID NOT NULL DEFAULT  uuid_generate_v4() UNIQUE,
+	room_id  BIGINT REFERENCES testing.room(id) NOT NULL,
+	reason   VARCHAR(200) NOT NULL,
+	time_slot   TSTZRANGE NOT NULL,
+	CONSTRAINT blackout_timeslot_half_open CHECK (lower_inc(time_slot) AND NOT upper_inc(time_slot)),
+	CONSTRAINT blackout_timeslot_overlap EXCLUDE USING gist (room_id WITH =, time_slot WITH &&)
+);
+
+
+-- Whether the room is blacked out at any time within the slot.
+CREATE FUNCTION testing.room_blacked_out(room_id BIGINT, time_slot TSTZRANGE)
+RETURNS BOOLEAN AS $$
+	SELECT EXISTS (SELECT true
+					 FROM testing.room_blackout bo
+					WHERE bo.room_id = $1
+					  AND bo.time_slot && $2);
+$$ LANGUAGE sql STABLE;
+
+
+-- Meetings may not overlap a blackout of their room, buffers included.  The
+-- violation is reported as room_blackout_overlap, so that it can be told apart
+-- from an overlap with another meeting.
+--
+-- Neither this trigger nor the one on room_blackout below can see rows the
+-- other has inserted but not yet committed, so both lock the room first: a
+-- meeting and a blackout booked concurrently in the same room then wait for
+-- one another, and the later one sees the earlier once it is committed.
+CREATE FUNCTION testing.check_room_blackout() RETURNS trigger AS $$
+BEGIN
+	PERFORM true
+	   FROM testing.room
+	  WHERE id = NEW.room_id
+		FOR UPDATE;
+
+	IF testing.room_blacked_out(NEW.room_id, NEW.blocked_slot) THEN
+		RAISE EXCEPTION 'Room % is blacked out during %', NEW.room_id, NEW.time_slot
+			USING ERRCODE = 'exclusion_violation', CONSTRAINT = 'room_blackout_overlap';
+	END IF;
+	RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- triggers fire in order of name, so blocked_slot has been derived by now
+CREATE TRIGGER mtg_room_blackout
+	BEFORE INSERT OR UPDATE OF room_id, time_slot ON testing.meeting
+	FOR EACH ROW EXECUTE PROCEDURE testing.check_room_blackout();
+
+
+-- Nor may a blackout be added over meetings already booked.
+CREATE FUNCTION testing.check_blackout_meetings() RETURNS trigger AS $$
+BEGIN
+	PERFORM true
+	   FROM testing.room
+	  WHERE id = NEW.room_id
+		FOR UPDATE;
+
+	IF EXISTS (SELECT true
+				 FROM testing.meeting mtg
+				WHERE mtg.room_id = NEW.room_id
+				  AND mtg.blocked_slot && NEW.time_slot) THEN
+		RAISE EXCEPTION 'Room % has meetings booked during %', NEW.room_id, NEW.time_slot
+			USING ERRCODE = 'exclusion_violation', CONSTRAINT = 'blackout_meeting_overlap';
+	END IF;
+	RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER blackout_meetings
+	BEFORE INSERT OR UPDATE OF room_id, time_slot ON testing.room_blackout
+	FOR EACH ROW EXECUTE PROCEDURE testing.check_blackout_meetings();
+
+
+CREATE TABLE testing.meeting_series_exception (
+	id  BIGSERIAL PRIMARY KEY,
+	series_id   BIGINT REFERENCES testing.meeting_series(id) ON DELETE CASCADE NOT NULL,
+	occurrence_start   TIMESTAMPTZ NOT NULL,
+	UNIQUE(series_id, occurrence_start)
+);
+
+
+CREATE TABLE testing.meeting_attendee (
+	id  BIGSERIAL PRIMARY KEY,
+	meeting_id  BIGINT REFERENCES testing.meeting(id) ON DELETE CASCADE NOT NULL,
+	user_id  BIGINT REFERENCES testing.users(id) NOT NULL,
+	is_required   BOOLEAN NOT NULL DEFAULT true,
+	status   VARCHAR(10) NOT NULL DEFAULT 'invited'
+			 CHECK (status IN ('invited', 'accepted', 'declined', 'tentative')),
+	UNIQUE(meeting_id, user_id)
+);
+
+
+CREATE TABLE testing.waitlist_entry (
+	id  BIGSERIAL PRIMARY KEY,
+	ext_id UUID NOT NULL DEFAULT  uuid_generate_v4() UNIQUE,
+	requester_id BIGINT REFERENCES testing.users(id) NOT NULL,
+	room_id  BIGINT REFERENCES testing.room(id) NOT NULL,
+	title   VARCHAR(200) NOT NULL,
+	time_slot   TSTZRANGE NOT NULL,
+	headcount   INTEGER CHECK (headcount > 0),
+	conflict_policy   VARCHAR(5) NOT NULL DEFAULT 'error' CHECK (conflict_policy IN ('error', 'warn')),
+	status   VARCHAR(10) NOT NULL DEFAULT 'waiting' CHECK (status IN ('waiting', 'booked', 'failed')),
+	meeting_id  BIGINT REFERENCES testing.meeting(id) ON DELETE SET NULL,
+	created_at   TIMESTAMPTZ NOT NULL DEFAULT clock_timestamp(),
+	booked_at   TIMESTAMPTZ,
+	CONSTRAINT waitlist_timeslot_half_open CHECK (lower_inc(time_slot) AND NOT upper_inc(time_slot))
 );
@@ -0,0 +1,44 @@
+-- Time slots used to be booked as inclusive ranges, [start, end], so that a
+-- meeting ending at 10:00 conflicted with one starting at 10:00.  This
+-- normalizes the existing rows to half-open ranges, [start, end), and adds the
+-- checks that keep them that way.  Run it once against a database created
+-- from an earlier db.sql:
+--
+--	psql -U test_user -d testing_db -h 127.0.0.1 -f migrate_half_open_slots.sql
+
+BEGIN;
+
+-- rows are normalized one at a time, so overlaps are only checked again once
+-- all of them are done
+ALTER TABLE testing.meeting DROP CONSTRAINT mtg_timeslot_overlap;
+
+UPDATE testing.meeting
+   SET time_slot = tstzrange(lower(time_slot), upper(time_slot), '[)')
+ WHERE NOT lower_inc(time_slot)
+	OR upper_inc(time_slot);
+
+ALTER TABLE testing.meeting
+  ADD CONSTRAINT mtg_timeslot_overlap EXCLUDE USING gist (room_id WITH =, blocked_slot WITH &&),
+  ADD CONSTRAINT mtg_timeslot_half_open CHECK (lower_inc(time_slot) AND NOT upper_inc(time_slot));
+
+
+UPDATE testing.meeting_series
+   SET first_slot = tstzrange(lower(first_slot), upper(first_slot), '[)')
+ WHERE NOT lower_inc(first_slot)
+	OR upper_inc(first_slot);
+
+ALTER TABLE testing.meeting_series
+  ADD CONSTRAINT series_first_slot_half_open
+	  CHECK (lower_inc(first_slot) AND NOT upper_inc(first_slot));
+
+
+UPDATE testing.waitlist_entry
+   SET time_slot = tstzrange(lower(time_slot), upper(time_slot), '[)')
+ WHERE NOT lower_inc(time_slot)
+	OR upper_inc(time_slot);
+
+ALTER TABLE testing.waitlist_entry
+  ADD CONSTRAINT waitlist_timeslot_half_open
+	  CHECK (lower_inc(time_slot) AND NOT upper_inc(time_slot));
+
+COMMIT;
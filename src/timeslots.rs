@@ -0,0 +1,175 @@
+/*
+This module performs range arithmetic on time slots, such as finding the free
+gaps that remain in a window once busy time slots are taken out of it.
+*/
+use chrono::prelude::*;
+use chrono::Duration;
+use postgres_range::{BoundSided, BoundType, LowerBound, Range, RangeBound};
+
+use db::TSTZRange;
+
+/// The time slot of a booking from `start` up to, but not including, `end`,
+/// so that back-to-back bookings do not overlap.
+pub fn slot(start: DateTime<Utc>, end: DateTime<Utc>) -> TSTZRange {
+    range!('[' start, end; ')')
+}
+
+/// Daily hours during which bookings are suggested, in the local time of the
+/// building, see `Building::get_working_windows`.
+#[derive(Debug, Clone)]
+pub struct WorkingHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub weekdays: Vec<Weekday>,
+}
+impl WorkingHours {
+    /// Monday to Friday, from `start` to `end`.
+    pub fn weekdays(start: NaiveTime, end: NaiveTime) -> WorkingHours {
+        WorkingHours { start,
+                       end,
+                       weekdays: vec![Weekday::Mon,
+                                      Weekday::Tue,
+                                      Weekday::Wed,
+                                      Weekday::Thu,
+                                      Weekday::Fri], }
+    }
+}
+
+/// The parts of the `slots` that also fall within one of the `windows`.
+/// Both are expected in chronological order.
+pub fn mask(slots: &[TSTZRange], windows: &[TSTZRange]) -> Vec<TSTZRange> {
+    let mut masked = Vec::new();
+    for slot in slots {
+        for window in windows {
+            let part = slot.intersect(window);
+            if !part.is_empty() {
+                masked.push(part);
+            }
+        }
+    }
+
+    masked
+}
+
+/// Up to `max_results` consecutive time slots of `duration` fitting within the
+/// `gaps`, earliest first.  Gaps are taken to be half-open, like the time
+/// slots of bookings.
+pub fn fit_slots(gaps: &[TSTZRange], duration: Duration, max_results: usize) -> Vec<TSTZRange> {
+    let mut slots = Vec::new();
+
+    for gap in gaps {
+        let (mut start, end) = match (gap.lower(), gap.upper()) {
+            (Some(lower), Some(upper)) => (lower.value, upper.value),
+            _ => continue,
+        };
+
+        while start + duration <= end {
+            if slots.len() >= max_results {
+                return slots;
+            }
+
+            slots.push(slot(start, start + duration));
+            start += duration;
+        }
+    }
+
+    slots
+}
+
+/// Time slots of `duration` starting every `step` within each of the
+/// `windows`, earliest first.  The windows are taken to be half-open, like the
+/// time slots of bookings, and each candidate fits within its window.
+pub fn candidate_slots(windows: &[TSTZRange],
+                       duration: Duration,
+                       step: Duration)
+                       -> Vec<TSTZRange> {
+    let mut candidates = Vec::new();
+
+    for window in windows {
+        let (mut start, end) = match (window.lower(), window.upper()) {
+            (Some(lower), Some(upper)) => (lower.value, upper.value),
+            _ => continue,
+        };
+
+        while start + duration <= end {
+            candidates.push(slot(start, start + duration));
+            start += step;
+        }
+    }
+
+    candidates
+}
+
+/// `slot` less `before` at its start and `after` at its end, e.g. to leave
+/// time for setup and cleanup.  Unbounded ends stay unbounded.
+pub fn shrink(slot: &TSTZRange, before: Duration, after: Duration) -> TSTZRange {
+    if slot.is_empty() {
+        return *slot;
+    }
+
+    Range::new(slot.lower().map(|bound| RangeBound::new(bound.value + before, bound.type_)),
+               slot.upper().map(|bound| RangeBound::new(bound.value - after, bound.type_)))
+}
+
+/// The parts of `window` not covered by any of the `busy` time slots, in
+/// chronological order.  Busy time slots may overlap one another.
+pub fn free_gaps(window: &TSTZRange, busy: &[TSTZRange]) -> Vec<TSTZRange> {
+    let mut busy = busy.iter()
+                       .map(|slot| slot.intersect(window))
+                       .filter(|slot| !slot.is_empty())
+                       .collect::<Vec<TSTZRange>>();
+    busy.sort_by_key(|slot| slot.lower().map(|bound| bound.value));
+
+    let mut gaps = Vec::new();
+
+    // start of the next potential gap; None while still unbounded below
+    let mut cursor = window.lower().map(copy_bound);
+
+    for slot in &busy {
+        if let Some(start) = slot.lower() {
+            let gap = Range::new(cursor.as_ref().map(copy_bound),
+                                 Some(RangeBound::new(start.value, flip(start.type_))));
+            if !gap.is_empty() {
+                gaps.push(gap);
+            }
+        }
+
+        let next = match slot.upper() {
+            Some(end) => RangeBound::new(end.value, flip(end.type_)),
+            // busy until the end of time
+            None => return gaps,
+        };
+
+        cursor = match cursor {
+            Some(ref current) if !lower_precedes(current, &next) => Some(copy_bound(current)),
+            _ => Some(next),
+        };
+    }
+
+    let gap = Range::new(cursor, window.upper().map(copy_bound));
+    if !gap.is_empty() {
+        gaps.push(gap);
+    }
+
+    gaps
+}
+
+fn copy_bound<S: BoundSided>(bound: &RangeBound<S, DateTime<Utc>>) -> RangeBound<S, DateTime<Utc>> {
+    RangeBound::new(bound.value, bound.type_)
+}
+
+/// An upper bound becomes the lower bound of what follows it, and vice versa.
+fn flip(type_: BoundType) -> BoundType {
+    match type_ {
+        BoundType::Inclusive => BoundType::Exclusive,
+        BoundType::Exclusive => BoundType::Inclusive,
+    }
+}
+
+/// Whether lower bound `a` starts strictly before lower bound `b`.
+fn lower_precedes(a: &RangeBound<LowerBound, DateTime<Utc>>,
+                  b: &RangeBound<LowerBound, DateTime<Utc>>)
+                  -> bool {
+    a.value < b.value
+    || (a.value == b.value && a.type_ == BoundType::Inclusive && b.type_ == BoundType::Exclusive)
+}
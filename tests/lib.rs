@@ -3,7 +3,11 @@ extern crate assert_matches;
 extern crate chrono;
 extern crate pg_example;
 extern crate postgres;
+#[macro_use]
+extern crate postgres_range;
 extern crate rand;
 extern crate slog;
 
 mod test_db;
+mod test_recurrence;
+mod test_timeslots;
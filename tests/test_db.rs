@@ -1,11 +1,18 @@
+use chrono::{prelude::*, Duration};
 use postgres::{transaction::Transaction, Connection, TlsMode};
 use rand::{thread_rng, Rng};
 use slog::Logger;
 
 use pg_example::{
+    db::TSTZRange,
     errors::{DBError, MeetingError, MyError},
     log::create_logger,
-    models::{Building, Meeting, Room, User},
+    models::{Amenity, BookingRequest, BookingRules, Building, ConflictPolicy, Meeting,
+             MeetingFilter, MeetingSeries, MeetingStatus, OccurrenceKind, Priority, Room,
+             RoomFilter, RoomType, Rsvp, RsvpStatus, ScheduleOptions, SeriesUpdate, SlotStatus,
+             User, WaitlistEntry, WaitlistStatus},
+    recurrence::{Frequency, Recurrence, MAX_OCCURRENCES},
+    timeslots::{self, WorkingHours},
 };
 
 pub fn get_conn() -> Result<Connection, MyError> {
@@ -36,6 +43,65 @@ pub fn get_test_data(logger: &Logger, tx: &Transaction) -> Result<(User, Buildin
     Ok((user, building, room))
 }
 
+/// A random user, building and room to test with, per `get_test_data`.
+pub struct Fixture {
+    pub logger: Logger,
+    pub user: User,
+    pub building: Building,
+    pub room: Room,
+    conn: Connection,
+}
+impl Fixture {
+    pub fn new() -> Result<Fixture, MyError> {
+        let logger = create_logger();
+        let conn = get_conn()?;
+        let (user, building, room) = {
+            let tx = conn.transaction()
+                         .map_err(|err| MyError::DBError(DBError::PGError(err)))?;
+            get_test_data(&logger, &tx)?
+        };
+
+        Ok(Fixture { logger,
+                     user,
+                     building,
+                     room,
+                     conn, })
+    }
+
+    /// A transaction that is rolled back once the test is done with it.
+    pub fn transaction(&self) -> Result<Transaction<'_>, MyError> {
+        self.conn
+            .transaction()
+            .map_err(|err| MyError::DBError(DBError::PGError(err)))
+    }
+
+    /// Book the fixture's room for its user.
+    pub fn schedule(&self,
+                    start_dt: &str,
+                    end_dt: &str,
+                    tx: &Transaction)
+                    -> Result<Meeting, MyError> {
+        self.schedule_with(start_dt, end_dt, &ScheduleOptions::default(), tx)
+    }
+
+    pub fn schedule_with(&self,
+                         start_dt: &str,
+                         end_dt: &str,
+                         options: &ScheduleOptions,
+                         tx: &Transaction)
+                         -> Result<Meeting, MyError> {
+        Meeting::schedule_meeting(self.user.username.clone(),
+                                  self.building.ext_id,
+                                  self.room.code.clone(),
+                                  start_dt.to_string(),
+                                  end_dt.to_string(),
+                                  "Meeting".to_string(),
+                                  options,
+                                  &self.logger,
+                                  tx)
+    }
+}
+
 #[test]
 fn test_mtg_schedule() -> Result<(), MyError> {
     // This test successfully schedules an appointment and then immediately
@@ -59,6 +125,7 @@ fn test_mtg_schedule() -> Result<(), MyError> {
                                            "2018-09-25T17:00:00Z".to_string(),
                                            "2018-09-25T19:00:00Z".to_string(),
                                            "Meeting #1".to_string(),
+                                           &ScheduleOptions::default(),
                                            &logger,
                                            &tx);
     assert_eq!(true, result.is_ok());
@@ -69,6 +136,7 @@ fn test_mtg_schedule() -> Result<(), MyError> {
                                            "2018-09-25T17:00:00Z".to_string(),
                                            "2018-09-25T19:00:00Z".to_string(),
                                            "Meeting #2".to_string(),
+                                           &ScheduleOptions::default(),
                                            &logger,
                                            &tx);
     assert_matches!(result,
@@ -92,6 +160,7 @@ fn test_mtg_room_availability() -> Result<(), MyError> {
                                            "2018-09-25T09:00:00Z".to_string(),
                                            "2018-09-25T10:00:00Z".to_string(),
                                            "Meeting #1".to_string(),
+                                           &ScheduleOptions::default(),
                                            &logger,
                                            &tx);
     assert_eq!(true, result.is_ok());
@@ -99,9 +168,10 @@ fn test_mtg_room_availability() -> Result<(), MyError> {
     let result = Meeting::schedule_meeting(user.username.clone(),
                                            building.ext_id,
                                            room.code.clone(),
-                                           "2018-09-25T10:00:05Z".to_string(),
+                                           "2018-09-25T10:00:00Z".to_string(),
                                            "2018-09-25T11:00:00Z".to_string(),
                                            "Meeting #2".to_string(),
+                                           &ScheduleOptions::default(),
                                            &logger,
                                            &tx);
     assert_eq!(true, result.is_ok());
@@ -112,13 +182,14 @@ fn test_mtg_room_availability() -> Result<(), MyError> {
                                            "2018-09-25T14:00:00Z".to_string(),
                                            "2018-09-25T16:00:00Z".to_string(),
                                            "Meeting #3".to_string(),
+                                           &ScheduleOptions::default(),
                                            &logger,
                                            &tx);
     assert_eq!(true, result.is_ok());
 
     let preferred_timeslots: Vec<(i64, String, String)> =
         vec![(26, "2018-09-25T09:00:00Z".to_string(), "2018-09-25T11:00:00Z".to_string()),
-             (27, "2018-09-25T11:00:01Z".to_string(), "2018-09-25T13:00:00Z".to_string())];
+             (27, "2018-09-25T11:00:00Z".to_string(), "2018-09-25T13:00:00Z".to_string())];
 
     let result: Vec<i64> = Meeting::check_room_availability_v1(room.code,
                                                                building.ext_id,
@@ -130,3 +201,1673 @@ fn test_mtg_room_availability() -> Result<(), MyError> {
 
     Ok(())
 }
+
+#[test]
+fn test_mtg_cancel() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let other = User::get_users(&fx.logger, &tx)?.into_iter()
+                                                 .find(|u| u.id != fx.user.id && !u.is_admin)
+                                                 .unwrap();
+
+    let mtg = fx.schedule("2018-09-26T09:00:00Z", "2018-09-26T10:00:00Z", &tx)?;
+
+    let result = Meeting::cancel_meeting(mtg.ext_id, other.username.clone(), &fx.logger, &tx);
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::NotPermitted)));
+
+    let result = Meeting::cancel_meeting(mtg.ext_id, fx.user.username.clone(), &fx.logger, &tx);
+    assert_eq!(true, result.is_ok());
+
+    let result = Meeting::cancel_meeting(mtg.ext_id, fx.user.username.clone(), &fx.logger, &tx);
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::NotFound)));
+
+    // the time slot is free again
+    let result = Meeting::schedule_meeting(other.username.clone(),
+                                           fx.building.ext_id,
+                                           fx.room.code.clone(),
+                                           "2018-09-26T09:00:00Z".to_string(),
+                                           "2018-09-26T10:00:00Z".to_string(),
+                                           "Meeting #2".to_string(),
+                                           &ScheduleOptions::default(),
+                                           &fx.logger,
+                                           &tx);
+    assert_eq!(true, result.is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_mtg_reschedule() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let first = fx.schedule("2018-09-27T09:00:00Z", "2018-09-27T10:00:00Z", &tx)?;
+
+    let second = fx.schedule("2018-09-27T13:00:00Z", "2018-09-27T14:00:00Z", &tx)?;
+
+    let result = Meeting::reschedule(second.ext_id,
+                                     fx.user.username.clone(),
+                                     fx.room.code.clone(),
+                                     fx.building.ext_id,
+                                     "2018-09-27T09:30:00Z".to_string(),
+                                     "2018-09-27T10:30:00Z".to_string(),
+                                     &ScheduleOptions::default(),
+                                     &fx.logger,
+                                     &tx);
+    assert_matches!(result,
+                    Err(MyError::MeetingError(MeetingError::ScheduleConflict)));
+
+    // the original booking still holds its slot
+    let preferred_timeslots: Vec<(i64, String, String)> =
+        vec![(1, "2018-09-27T13:30:00Z".to_string(), "2018-09-27T13:45:00Z".to_string())];
+    let free = Meeting::check_room_availability_v1(fx.room.code.clone(),
+                                                   fx.building.ext_id,
+                                                   preferred_timeslots,
+                                                   &fx.logger,
+                                                   &tx)?;
+    assert_eq!(true, free.is_empty());
+
+    let moved = Meeting::reschedule(second.ext_id,
+                                    fx.user.username.clone(),
+                                    fx.room.code.clone(),
+                                    fx.building.ext_id,
+                                    "2018-09-27T15:00:00Z".to_string(),
+                                    "2018-09-27T16:00:00Z".to_string(),
+                                    &ScheduleOptions::default(),
+                                    &fx.logger,
+                                    &tx)?;
+    assert_eq!(second.ext_id, moved.ext_id);
+    assert_eq!(true, moved.ext_id != first.ext_id);
+
+    // only the organizer may move it, and only to a room that exists
+    let other = User::get_users(&fx.logger, &tx)?.into_iter()
+                                                 .find(|u| u.id != fx.user.id && !u.is_admin)
+                                                 .unwrap();
+    let result = Meeting::reschedule(second.ext_id,
+                                     other.username.clone(),
+                                     fx.room.code.clone(),
+                                     fx.building.ext_id,
+                                     "2018-09-27T17:00:00Z".to_string(),
+                                     "2018-09-27T18:00:00Z".to_string(),
+                                     &ScheduleOptions::default(),
+                                     &fx.logger,
+                                     &tx);
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::NotPermitted)));
+
+    let result = Meeting::reschedule(second.ext_id,
+                                     fx.user.username.clone(),
+                                     "NO-SUCH-ROOM".to_string(),
+                                     fx.building.ext_id,
+                                     "2018-09-27T17:00:00Z".to_string(),
+                                     "2018-09-27T18:00:00Z".to_string(),
+                                     &ScheduleOptions::default(),
+                                     &fx.logger,
+                                     &tx);
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::RoomNotFound)));
+
+    Ok(())
+}
+
+#[test]
+fn test_mtg_series() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    // block the third occurrence ahead of time
+    let result = fx.schedule("2018-10-03T09:00:00Z", "2018-10-03T09:30:00Z", &tx);
+    assert_eq!(true, result.is_ok());
+
+    let mut rule = Recurrence::new(Frequency::Daily);
+    rule.count = Some(5);
+
+    let (series, occurrences) = MeetingSeries::schedule_series(fx.user.username.clone(),
+                                                               fx.building.ext_id,
+                                                               fx.room.code.clone(),
+                                                               "2018-10-01T09:00:00Z".to_string(),
+                                                               "2018-10-01T09:15:00Z".to_string(),
+                                                               "Standup".to_string(),
+                                                               rule,
+                                                               &fx.logger,
+                                                               &tx)?;
+    assert_eq!(5, occurrences.len());
+    assert_matches!(occurrences[2].result, Err(MeetingError::ScheduleConflict));
+
+    let booked = occurrences.iter()
+                            .filter_map(|occ| occ.result.as_ref().ok())
+                            .collect::<Vec<&Meeting>>();
+    assert_eq!(4, booked.len());
+    assert_eq!(true, booked.iter().all(|mtg| mtg.series_id == Some(series.id)));
+
+    // a series is booked in full or not at all
+    let mut rule = Recurrence::new(Frequency::Daily);
+    rule.count = Some(MAX_OCCURRENCES as u32 + 1);
+    let result = MeetingSeries::schedule_series(fx.user.username.clone(),
+                                                fx.building.ext_id,
+                                                fx.room.code.clone(),
+                                                "2019-10-01T09:00:00Z".to_string(),
+                                                "2019-10-01T09:15:00Z".to_string(),
+                                                "Standup".to_string(),
+                                                rule.clone(),
+                                                &fx.logger,
+                                                &tx);
+    assert_matches!(result, Err(MyError::ValueError(None)));
+
+    rule.count = Some(5);
+    let result = MeetingSeries::schedule_series(fx.user.username.clone(),
+                                                fx.building.ext_id,
+                                                "NO-SUCH-ROOM".to_string(),
+                                                "2019-10-01T09:00:00Z".to_string(),
+                                                "2019-10-01T09:15:00Z".to_string(),
+                                                "Standup".to_string(),
+                                                rule,
+                                                &fx.logger,
+                                                &tx);
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::RoomNotFound)));
+
+    Ok(())
+}
+
+#[test]
+fn test_mtg_series_exceptions() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let mut rule = Recurrence::new(Frequency::Weekly);
+    rule.count = Some(4);
+
+    let (series, _) = MeetingSeries::schedule_series(fx.user.username.clone(),
+                                                     fx.building.ext_id,
+                                                     fx.room.code.clone(),
+                                                     "2018-11-05T10:00:00Z".to_string(),
+                                                     "2018-11-05T11:00:00Z".to_string(),
+                                                     "Weekly sync".to_string(),
+                                                     rule,
+                                                     &fx.logger,
+                                                     &tx)?;
+
+    MeetingSeries::cancel_occurrence(series.ext_id,
+                                     "2018-11-12T10:00:00Z".to_string(),
+                                     fx.user.username.clone(),
+                                     &fx.logger,
+                                     &tx)?;
+
+    let moved = MeetingSeries::move_occurrence(series.ext_id,
+                                               "2018-11-19T10:00:00Z".to_string(),
+                                               fx.room.code.clone(),
+                                               fx.building.ext_id,
+                                               "2018-11-20T10:00:00Z".to_string(),
+                                               "2018-11-20T11:00:00Z".to_string(),
+                                               fx.user.username.clone(),
+                                               &ScheduleOptions::default(),
+                                               &fx.logger,
+                                               &tx)?;
+    assert_eq!(Some(OccurrenceKind::Modified), moved.occurrence_kind());
+
+    let occurrences = MeetingSeries::get_occurrences(series.ext_id, &fx.logger, &tx)?;
+    let kinds = occurrences.iter()
+                           .map(|mtg| mtg.occurrence_kind())
+                           .collect::<Vec<Option<OccurrenceKind>>>();
+    assert_eq!(vec![Some(OccurrenceKind::Generated),
+                    Some(OccurrenceKind::Modified),
+                    Some(OccurrenceKind::Generated)],
+               kinds);
+
+    let cancelled = MeetingSeries::get_cancelled_occurrences(series.ext_id, &fx.logger, &tx)?;
+    assert_eq!(1, cancelled.len());
+
+    let invitee = User::get_users(&fx.logger, &tx)?.into_iter()
+                                                   .find(|u| u.id != fx.user.id)
+                                                   .unwrap();
+    let last = occurrences[2].ext_id;
+    Meeting::invite(last, vec![invitee.ext_id], true, ConflictPolicy::Warn, &fx.logger, &tx)?;
+    Meeting::respond(last, invitee.ext_id, Rsvp::Accept, &fx.logger, &tx)?;
+
+    // renaming the series from the second week on keeps its exceptions, and
+    // the attendees along with their responses
+    let mut rule = Recurrence::new(Frequency::Weekly);
+    rule.count = Some(3);
+    let update = SeriesUpdate { bldg_ext_id: fx.building.ext_id,
+                                room_code: fx.room.code.clone(),
+                                start_dt: "2018-11-12T10:00:00Z".to_string(),
+                                end_dt: "2018-11-12T11:00:00Z".to_string(),
+                                title: "Weekly review".to_string(),
+                                recurrence: rule, };
+
+    let (renamed, occurrences) = MeetingSeries::update_from(series.ext_id,
+                                                            "2018-11-06T00:00:00Z".to_string(),
+                                                            &update,
+                                                            fx.user.username.clone(),
+                                                            &fx.logger,
+                                                            &tx)?;
+    assert_eq!(1, occurrences.len());
+    assert_eq!(true, occurrences[0].result.is_ok());
+
+    let remaining = MeetingSeries::get_occurrences(series.ext_id, &fx.logger, &tx)?;
+    assert_eq!(1, remaining.len());
+
+    let occurrences = MeetingSeries::get_occurrences(renamed.ext_id, &fx.logger, &tx)?;
+    let kinds = occurrences.iter()
+                           .map(|mtg| mtg.occurrence_kind())
+                           .collect::<Vec<Option<OccurrenceKind>>>();
+    assert_eq!(vec![Some(OccurrenceKind::Modified), Some(OccurrenceKind::Generated)],
+               kinds);
+
+    let cancelled = MeetingSeries::get_cancelled_occurrences(renamed.ext_id, &fx.logger, &tx)?;
+    assert_eq!(1, cancelled.len());
+
+    let attendees = Meeting::get_attendees(occurrences[1].ext_id, &fx.logger, &tx)?;
+    assert_eq!(1, attendees.len());
+    assert_eq!(invitee.id, attendees[0].user.id);
+    assert_eq!(RsvpStatus::Accepted, attendees[0].status);
+
+    // from the last occurrence on, the series moves to the afternoon, and the
+    // attendees are asked again
+    let mut rule = Recurrence::new(Frequency::Weekly);
+    rule.count = Some(3);
+    let update = SeriesUpdate { start_dt: "2018-11-26T14:00:00Z".to_string(),
+                                end_dt: "2018-11-26T15:00:00Z".to_string(),
+                                recurrence: rule,
+                                ..update };
+
+    let (moved, occurrences) = MeetingSeries::update_from(renamed.ext_id,
+                                                          "2018-11-26T00:00:00Z".to_string(),
+                                                          &update,
+                                                          fx.user.username.clone(),
+                                                          &fx.logger,
+                                                          &tx)?;
+    assert_eq!(3, occurrences.len());
+    assert_eq!(true, occurrences.iter().all(|occ| occ.result.is_ok()));
+
+    let remaining = MeetingSeries::get_occurrences(renamed.ext_id, &fx.logger, &tx)?;
+    assert_eq!(1, remaining.len());
+
+    let occurrences = MeetingSeries::get_occurrences(moved.ext_id, &fx.logger, &tx)?;
+    for mtg in &occurrences {
+        let attendees = Meeting::get_attendees(mtg.ext_id, &fx.logger, &tx)?;
+        assert_eq!(1, attendees.len());
+        assert_eq!(RsvpStatus::Invited, attendees[0].status);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_mtg_attendees() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let invitees = User::get_users(&fx.logger, &tx)?.into_iter()
+                                                    .filter(|u| u.id != fx.user.id)
+                                                    .take(2)
+                                                    .collect::<Vec<User>>();
+
+    let mtg = fx.schedule("2018-09-28T09:00:00Z", "2018-09-28T10:00:00Z", &tx)?;
+
+    let attendees = Meeting::invite(mtg.ext_id,
+                                    invitees.iter().map(|u| u.ext_id).collect(),
+                                    true,
+                                    ConflictPolicy::Error,
+                                    &fx.logger,
+                                    &tx)?;
+    assert_eq!(2, attendees.len());
+    assert_eq!(true, attendees.iter().all(|a| a.status == RsvpStatus::Invited));
+
+    let attendee = Meeting::respond(mtg.ext_id, invitees[0].ext_id, Rsvp::Accept, &fx.logger, &tx)?;
+    assert_eq!(RsvpStatus::Accepted, attendee.status);
+
+    // the organizer was not invited
+    let result = Meeting::respond(mtg.ext_id, fx.user.ext_id, Rsvp::Decline, &fx.logger, &tx);
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::NotFound)));
+
+    let attendees = Meeting::get_attendees(mtg.ext_id, &fx.logger, &tx)?;
+    let accepted = attendees.iter()
+                            .find(|a| a.user.ext_id == invitees[0].ext_id)
+                            .unwrap();
+    assert_eq!(RsvpStatus::Accepted, accepted.status);
+
+    Ok(())
+}
+
+#[test]
+fn test_mtg_attendee_conflict() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let other_code = if fx.room.code == "2A" { "3A" } else { "2A" }.to_string();
+    let invitee = User::get_users(&fx.logger, &tx)?.into_iter()
+                                                   .find(|u| u.id != fx.user.id)
+                                                   .unwrap();
+
+    let first = fx.schedule("2018-09-29T09:00:00Z", "2018-09-29T10:00:00Z", &tx)?;
+
+    // the organizer cannot be in two rooms at once
+    let result = Meeting::schedule_meeting(fx.user.username.clone(),
+                                           fx.building.ext_id,
+                                           other_code.clone(),
+                                           "2018-09-29T09:30:00Z".to_string(),
+                                           "2018-09-29T10:30:00Z".to_string(),
+                                           "Meeting #2".to_string(),
+                                           &ScheduleOptions::default(),
+                                           &fx.logger,
+                                           &tx);
+    match result {
+        Err(MyError::MeetingError(MeetingError::AttendeeConflict { user: conflicted,
+                                                                  conflicting_meeting, })) => {
+            assert_eq!(fx.user.ext_id, conflicted);
+            assert_eq!(first.ext_id, conflicting_meeting);
+        }
+        other => panic!("expected an attendee conflict, got {:?}", other),
+    }
+
+    let options = ScheduleOptions { conflict_policy: ConflictPolicy::Warn,
+                                    ..ScheduleOptions::default() };
+    let second = Meeting::schedule_meeting(fx.user.username.clone(),
+                                           fx.building.ext_id,
+                                           other_code.clone(),
+                                           "2018-09-29T09:30:00Z".to_string(),
+                                           "2018-09-29T10:30:00Z".to_string(),
+                                           "Meeting #2".to_string(),
+                                           &options,
+                                           &fx.logger,
+                                           &tx)?;
+    let conflicts = Meeting::get_attendee_conflicts(second.ext_id, &fx.logger, &tx)?;
+    assert_eq!(1, conflicts.len());
+
+    // moving the meeting keeps the policy it is given
+    let result = Meeting::reschedule(second.ext_id,
+                                     fx.user.username.clone(),
+                                     other_code.clone(),
+                                     fx.building.ext_id,
+                                     "2018-09-29T09:15:00Z".to_string(),
+                                     "2018-09-29T10:15:00Z".to_string(),
+                                     &ScheduleOptions::default(),
+                                     &fx.logger,
+                                     &tx);
+    assert_matches!(result,
+                    Err(MyError::MeetingError(MeetingError::AttendeeConflict { .. })));
+    let second = Meeting::reschedule(second.ext_id,
+                                     fx.user.username.clone(),
+                                     other_code.clone(),
+                                     fx.building.ext_id,
+                                     "2018-09-29T09:15:00Z".to_string(),
+                                     "2018-09-29T10:15:00Z".to_string(),
+                                     &options,
+                                     &fx.logger,
+                                     &tx)?;
+
+    // a required attendee booked elsewhere is a conflict too
+    Meeting::invite(first.ext_id,
+                    vec![invitee.ext_id],
+                    true,
+                    ConflictPolicy::Error,
+                    &fx.logger,
+                    &tx)?;
+    let result = Meeting::invite(second.ext_id,
+                                 vec![invitee.ext_id],
+                                 true,
+                                 ConflictPolicy::Error,
+                                 &fx.logger,
+                                 &tx);
+    assert_matches!(result,
+                    Err(MyError::MeetingError(MeetingError::AttendeeConflict { .. })));
+
+    // but an optional one is not
+    let attendees = Meeting::invite(second.ext_id,
+                                    vec![invitee.ext_id],
+                                    false,
+                                    ConflictPolicy::Error,
+                                    &fx.logger,
+                                    &tx)?;
+    assert_eq!(1, attendees.len());
+
+    Ok(())
+}
+
+#[test]
+fn test_mtg_room_capacity() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    // "A" rooms seat 4
+    let small_room = "2A".to_string();
+
+    let options = ScheduleOptions { headcount: Some(5),
+                                    ..ScheduleOptions::default() };
+    let result = Meeting::schedule_meeting(fx.user.username.clone(),
+                                           fx.building.ext_id,
+                                           small_room.clone(),
+                                           "2018-09-30T09:00:00Z".to_string(),
+                                           "2018-09-30T10:00:00Z".to_string(),
+                                           "Meeting #1".to_string(),
+                                           &options,
+                                           &fx.logger,
+                                           &tx);
+    assert_matches!(result,
+                    Err(MyError::MeetingError(MeetingError::CapacityExceeded { capacity: 4,
+                                                                               headcount: 5, })));
+
+    let options = ScheduleOptions { headcount: Some(4),
+                                    ..ScheduleOptions::default() };
+    let mtg = Meeting::schedule_meeting(fx.user.username.clone(),
+                                        fx.building.ext_id,
+                                        small_room.clone(),
+                                        "2018-09-30T09:00:00Z".to_string(),
+                                        "2018-09-30T10:00:00Z".to_string(),
+                                        "Meeting #1".to_string(),
+                                        &options,
+                                        &fx.logger,
+                                        &tx)?;
+
+    // with the organizer, four invitees no longer fit
+    let invitees = User::get_users(&fx.logger, &tx)?.into_iter()
+                                                    .filter(|u| u.id != fx.user.id)
+                                                    .take(4)
+                                                    .map(|u| u.ext_id)
+                                                    .collect::<Vec<_>>();
+    let result = Meeting::invite(mtg.ext_id,
+                                 invitees,
+                                 false,
+                                 ConflictPolicy::Warn,
+                                 &fx.logger,
+                                 &tx);
+    assert_matches!(result,
+                    Err(MyError::MeetingError(MeetingError::CapacityExceeded { .. })));
+
+    Ok(())
+}
+
+#[test]
+fn test_room_find_available() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let result = Meeting::schedule_meeting(fx.user.username.clone(),
+                                           fx.building.ext_id,
+                                           "4B".to_string(),
+                                           "2018-10-01T14:00:00Z".to_string(),
+                                           "2018-10-01T15:00:00Z".to_string(),
+                                           "Meeting #1".to_string(),
+                                           &ScheduleOptions::default(),
+                                           &fx.logger,
+                                           &tx);
+    assert_eq!(true, result.is_ok());
+
+    let start = Utc.with_ymd_and_hms(2018, 10, 1, 14, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2018, 10, 1, 15, 0, 0).unwrap();
+    let filters = RoomFilter { min_capacity: Some(8),
+                               preferred_floor: Some(4),
+                               ..RoomFilter::default() };
+    let rooms = Room::find_available(fx.building.ext_id,
+                                     range!('[' start, end; ']'),
+                                     &filters,
+                                     &fx.logger,
+                                     &tx)?;
+
+    // 4B is taken, so 4C is the best fit on the preferred floor
+    assert_eq!("4C", rooms[0].code);
+    assert_eq!(true, rooms.iter().all(|r| r.capacity >= 8 && r.code != "4B"));
+
+    Ok(())
+}
+
+#[test]
+fn test_room_free_busy() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let mtg = fx.schedule("2018-10-02T11:00:00Z", "2018-10-02T12:00:00Z", &tx)?;
+
+    let start = Utc.with_ymd_and_hms(2018, 10, 2, 8, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2018, 10, 2, 18, 0, 0).unwrap();
+    let free_busy = Room::get_free_busy(fx.building.ext_id,
+                                        fx.room.code.clone(),
+                                        range!('[' start, end; ']'),
+                                        &fx.logger,
+                                        &tx)?;
+
+    assert_eq!(1, free_busy.busy.len());
+    assert_eq!(mtg.ext_id, free_busy.busy[0].meeting_ext_id);
+    assert_eq!(2, free_busy.free.len());
+
+    Ok(())
+}
+
+#[test]
+fn test_mtg_suggest_slots() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let result = fx.schedule("2018-10-02T09:00:00Z", "2018-10-02T10:30:00Z", &tx);
+    assert_eq!(true, result.is_ok());
+
+    let start = Utc.with_ymd_and_hms(2018, 10, 2, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2018, 10, 3, 0, 0, 0).unwrap();
+    let hours = WorkingHours::weekdays(NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                                       NaiveTime::from_hms_opt(17, 0, 0).unwrap());
+    let slots = Meeting::suggest_slots(fx.building.ext_id,
+                                       fx.room.code.clone(),
+                                       Duration::hours(1),
+                                       range!('[' start, end; ')'),
+                                       3,
+                                       Some(&hours),
+                                       &[],
+                                       &fx.logger,
+                                       &tx)?;
+
+    let starts = slots.iter()
+                      .map(|slot| slot.lower().unwrap().value)
+                      .collect::<Vec<DateTime<Utc>>>();
+    assert_eq!(vec![Utc.with_ymd_and_hms(2018, 10, 2, 10, 30, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2018, 10, 2, 11, 30, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2018, 10, 2, 12, 30, 0).unwrap()],
+               starts);
+
+    // a blackout counts as a conflict, as it does when checking availability
+    Room::add_blackout(fx.building.ext_id,
+                       fx.room.code.clone(),
+                       "2018-10-02T11:45:00Z".to_string(),
+                       "2018-10-02T12:15:00Z".to_string(),
+                       "Cleaning the windows".to_string(),
+                       &fx.logger,
+                       &tx)?;
+    let slots = Meeting::suggest_slots(fx.building.ext_id,
+                                       fx.room.code.clone(),
+                                       Duration::hours(1),
+                                       range!('[' start, end; ')'),
+                                       3,
+                                       Some(&hours),
+                                       &[],
+                                       &fx.logger,
+                                       &tx)?;
+    let starts = slots.iter()
+                      .map(|slot| slot.lower().unwrap().value)
+                      .collect::<Vec<DateTime<Utc>>>();
+    assert_eq!(vec![Utc.with_ymd_and_hms(2018, 10, 2, 10, 30, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2018, 10, 2, 12, 15, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2018, 10, 2, 13, 15, 0).unwrap()],
+               starts);
+
+    Ok(())
+}
+
+#[test]
+fn test_user_common_free_time() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let users = User::get_users(&fx.logger, &tx)?.into_iter()
+                                                 .take(3)
+                                                 .collect::<Vec<User>>();
+
+    let result = Meeting::schedule_meeting(users[0].username.clone(),
+                                           fx.building.ext_id,
+                                           fx.room.code.clone(),
+                                           "2018-10-09T09:00:00Z".to_string(),
+                                           "2018-10-09T10:00:00Z".to_string(),
+                                           "Meeting #1".to_string(),
+                                           &ScheduleOptions::default(),
+                                           &fx.logger,
+                                           &tx);
+    assert_eq!(true, result.is_ok());
+
+    let mtg = Meeting::schedule_meeting(users[1].username.clone(),
+                                        fx.building.ext_id,
+                                        fx.room.code.clone(),
+                                        "2018-10-09T11:00:00Z".to_string(),
+                                        "2018-10-09T11:45:00Z".to_string(),
+                                        "Meeting #2".to_string(),
+                                        &ScheduleOptions::default(),
+                                        &fx.logger,
+                                        &tx)?;
+    Meeting::invite(mtg.ext_id,
+                    vec![users[2].ext_id],
+                    true,
+                    ConflictPolicy::Error,
+                    &fx.logger,
+                    &tx)?;
+
+    let start = Utc.with_ymd_and_hms(2018, 10, 9, 8, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2018, 10, 9, 13, 0, 0).unwrap();
+    let free = User::find_common_free_time(users.iter().map(|u| u.ext_id).collect(),
+                                           range!('[' start, end; ')'),
+                                           Some(Duration::minutes(30)),
+                                           Some(fx.building.ext_id),
+                                           &RoomFilter::default(),
+                                           &fx.logger,
+                                           &tx)?;
+
+    // 8-9, 10-11 and 11:45-13
+    assert_eq!(3, free.len());
+    assert_eq!(true, free.iter().all(|slot| slot.room.is_some()));
+
+    let free = User::find_common_free_time(users.iter().map(|u| u.ext_id).collect(),
+                                           range!('[' start, end; ')'),
+                                           Some(Duration::minutes(70)),
+                                           None,
+                                           &RoomFilter::default(),
+                                           &fx.logger,
+                                           &tx)?;
+
+    // only 11:45-13 lasts longer than an hour
+    assert_eq!(1, free.len());
+    assert_eq!(true, free[0].room.is_none());
+
+    let result = User::find_common_free_time(Vec::new(),
+                                             range!('[' start, end; ')'),
+                                             None,
+                                             None,
+                                             &RoomFilter::default(),
+                                             &fx.logger,
+                                             &tx);
+    assert_matches!(result, Err(MyError::ValueError(None)));
+
+    Ok(())
+}
+
+#[test]
+fn test_mtg_hold() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let other = User::get_users(&fx.logger, &tx)?.into_iter()
+                                                 .find(|u| u.id != fx.user.id)
+                                                 .unwrap();
+
+    let hold = Meeting::hold_room(fx.user.username.clone(),
+                                  fx.building.ext_id,
+                                  fx.room.code.clone(),
+                                  "2018-10-10T09:00:00Z".to_string(),
+                                  "2018-10-10T10:00:00Z".to_string(),
+                                  "Meeting #1".to_string(),
+                                  Duration::minutes(15),
+                                  &ScheduleOptions::default(),
+                                  &fx.logger,
+                                  &tx)?;
+    assert_eq!(MeetingStatus::Hold, hold.status);
+
+    // the hold blocks the room
+    let result = Meeting::schedule_meeting(other.username.clone(),
+                                           fx.building.ext_id,
+                                           fx.room.code.clone(),
+                                           "2018-10-10T09:30:00Z".to_string(),
+                                           "2018-10-10T10:30:00Z".to_string(),
+                                           "Meeting #2".to_string(),
+                                           &ScheduleOptions::default(),
+                                           &fx.logger,
+                                           &tx);
+    assert_matches!(result,
+                    Err(MyError::MeetingError(MeetingError::ScheduleConflict)));
+
+    let mtg = Meeting::confirm_hold(hold.ext_id, fx.user.username.clone(), &fx.logger, &tx)?;
+    assert_eq!(MeetingStatus::Confirmed, mtg.status);
+    assert_eq!(None, mtg.hold_expires_at);
+
+    // a hold that is not confirmed in time is released
+    let hold = Meeting::hold_room(fx.user.username.clone(),
+                                  fx.building.ext_id,
+                                  fx.room.code.clone(),
+                                  "2018-10-10T13:00:00Z".to_string(),
+                                  "2018-10-10T14:00:00Z".to_string(),
+                                  "Meeting #3".to_string(),
+                                  Duration::minutes(15),
+                                  &ScheduleOptions::default(),
+                                  &fx.logger,
+                                  &tx)?;
+
+    // backdate the hold rather than wait for it to expire
+    tx.execute("UPDATE testing.meeting
+                   SET hold_expires_at = clock_timestamp() - interval '1 minute'
+                 WHERE ext_id = $1;",
+               &[&hold.ext_id])
+      .map_err(|err| MyError::DBError(DBError::PGError(err)))?;
+
+    let result = Meeting::confirm_hold(hold.ext_id, fx.user.username.clone(), &fx.logger, &tx);
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::HoldExpired)));
+
+    let released = Meeting::release_expired_holds(&fx.logger, &tx)?;
+    assert_eq!(true, released.iter().any(|mtg| mtg.ext_id == hold.ext_id));
+
+    Ok(())
+}
+
+#[test]
+fn test_mtg_waitlist() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let others = User::get_users(&fx.logger, &tx)?.into_iter()
+                                                  .filter(|u| u.id != fx.user.id)
+                                                  .take(2)
+                                                  .collect::<Vec<User>>();
+
+    let mtg = fx.schedule("2029-10-11T09:00:00Z", "2029-10-11T11:00:00Z", &tx)?;
+
+    // both requests overlap the meeting, and each other
+    for other in &others {
+        let entry = WaitlistEntry::join(other.username.clone(),
+                                        fx.building.ext_id,
+                                        fx.room.code.clone(),
+                                        "2029-10-11T10:00:00Z".to_string(),
+                                        "2029-10-11T10:30:00Z".to_string(),
+                                        "Waitlisted".to_string(),
+                                        &ScheduleOptions::default(),
+                                        &fx.logger,
+                                        &tx)?;
+        assert_eq!(WaitlistStatus::Waiting, entry.status);
+        assert_eq!(None, entry.meeting_ext_id);
+    }
+
+    // shortening the meeting books the first request in line
+    Meeting::reschedule(mtg.ext_id,
+                        fx.user.username.clone(),
+                        fx.room.code.clone(),
+                        fx.building.ext_id,
+                        "2029-10-11T09:00:00Z".to_string(),
+                        "2029-10-11T09:30:00Z".to_string(),
+                        &ScheduleOptions::default(),
+                        &fx.logger,
+                        &tx)?;
+
+    let first = WaitlistEntry::get_entries(others[0].username.clone(), &fx.logger, &tx)?;
+    assert_eq!(WaitlistStatus::Booked, first[0].status);
+    let booked = first[0].meeting_ext_id.unwrap();
+
+    let second = WaitlistEntry::get_entries(others[1].username.clone(), &fx.logger, &tx)?;
+    assert_eq!(WaitlistStatus::Waiting, second[0].status);
+
+    // cancelling that booking makes room for the next one
+    Meeting::cancel_meeting(booked, others[0].username.clone(), &fx.logger, &tx)?;
+
+    let second = WaitlistEntry::get_entries(others[1].username.clone(), &fx.logger, &tx)?;
+    assert_eq!(WaitlistStatus::Booked, second[0].status);
+    assert_eq!(true, second[0].meeting_ext_id.is_some());
+
+    // a request too large for the room fails instead of waiting forever
+    let capacity =
+        Room::get_room(fx.building.ext_id, fx.room.code.clone(), &fx.logger, &tx)?.capacity;
+    let options = ScheduleOptions { headcount: Some(capacity + 1),
+                                    ..ScheduleOptions::default() };
+    let entry = WaitlistEntry::join(fx.user.username.clone(),
+                                    fx.building.ext_id,
+                                    fx.room.code.clone(),
+                                    "2029-10-11T10:00:00Z".to_string(),
+                                    "2029-10-11T10:30:00Z".to_string(),
+                                    "Too large".to_string(),
+                                    &options,
+                                    &fx.logger,
+                                    &tx)?;
+    assert_eq!(WaitlistStatus::Waiting, entry.status);
+
+    Meeting::cancel_meeting(second[0].meeting_ext_id.unwrap(),
+                            others[1].username.clone(),
+                            &fx.logger,
+                            &tx)?;
+
+    let entries = WaitlistEntry::get_entries(fx.user.username.clone(), &fx.logger, &tx)?;
+    assert_eq!(WaitlistStatus::Failed, entries[0].status);
+    assert_eq!(None, entries[0].meeting_ext_id);
+
+    // a request whose time slot has already started fails too
+    let entry = WaitlistEntry::join(fx.user.username.clone(),
+                                    fx.building.ext_id,
+                                    fx.room.code.clone(),
+                                    "2018-10-11T10:00:00Z".to_string(),
+                                    "2018-10-11T10:30:00Z".to_string(),
+                                    "Too late".to_string(),
+                                    &ScheduleOptions::default(),
+                                    &fx.logger,
+                                    &tx)?;
+    assert_eq!(WaitlistStatus::Failed, entry.status);
+
+    // the conflict policy applies once a request is booked: the organizer is
+    // already in the original meeting at 09:00
+    let other_code = if fx.room.code == "2A" { "3A" } else { "2A" }.to_string();
+    let entry = WaitlistEntry::join(fx.user.username.clone(),
+                                    fx.building.ext_id,
+                                    other_code.clone(),
+                                    "2029-10-11T09:00:00Z".to_string(),
+                                    "2029-10-11T09:30:00Z".to_string(),
+                                    "Conflicting".to_string(),
+                                    &ScheduleOptions::default(),
+                                    &fx.logger,
+                                    &tx)?;
+    assert_eq!(ConflictPolicy::Error, entry.conflict_policy);
+    assert_eq!(WaitlistStatus::Failed, entry.status);
+
+    let options = ScheduleOptions { conflict_policy: ConflictPolicy::Warn,
+                                    ..ScheduleOptions::default() };
+    let entry = WaitlistEntry::join(fx.user.username.clone(),
+                                    fx.building.ext_id,
+                                    other_code,
+                                    "2029-10-11T09:00:00Z".to_string(),
+                                    "2029-10-11T09:30:00Z".to_string(),
+                                    "Conflicting".to_string(),
+                                    &options,
+                                    &fx.logger,
+                                    &tx)?;
+    assert_eq!(WaitlistStatus::Booked, entry.status);
+
+    Ok(())
+}
+
+#[test]
+fn test_mtg_no_show_release() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    // without a grace period, meetings would be released right as they start
+    let result = Building::set_checkin_grace(fx.building.ext_id, Duration::zero(), &fx.logger, &tx);
+    assert_matches!(result, Err(MyError::ValueError(None)));
+
+    let building =
+        Building::set_checkin_grace(fx.building.ext_id, Duration::minutes(45), &fx.logger, &tx)?;
+    assert_eq!(45, building.checkin_grace_minutes);
+
+    let rooms = Room::get_rooms(&fx.logger, &tx)?.into_iter()
+                                                 .filter(|r| r.building_id == building.id)
+                                                 .take(2)
+                                                 .collect::<Vec<Room>>();
+
+    // both meetings started 30 minutes ago, but only one is checked in
+    let options = ScheduleOptions { conflict_policy: ConflictPolicy::Warn,
+                                    ..ScheduleOptions::default() };
+    let mut mtgs = Vec::new();
+    for room in &rooms {
+        mtgs.push(Meeting::schedule_meeting(fx.user.username.clone(),
+                                            building.ext_id,
+                                            room.code.clone(),
+                                            "2029-10-11T09:00:00Z".to_string(),
+                                            "2029-10-11T10:30:00Z".to_string(),
+                                            "Meeting".to_string(),
+                                            &options,
+                                            &fx.logger,
+                                            &tx)?);
+    }
+
+    // backdate the meetings rather than book them around the current time
+    for mtg in &mtgs {
+        tx.execute("UPDATE testing.meeting
+                       SET time_slot = tstzrange(clock_timestamp() - interval '30 minutes',
+                                                 clock_timestamp() + interval '60 minutes')
+                     WHERE ext_id = $1;",
+                   &[&mtg.ext_id])
+          .map_err(|err| MyError::DBError(DBError::PGError(err)))?;
+    }
+
+    let others = User::get_users(&fx.logger, &tx)?.into_iter()
+                                                  .filter(|u| u.id != fx.user.id)
+                                                  .take(2)
+                                                  .collect::<Vec<User>>();
+    Meeting::invite(mtgs[1].ext_id,
+                    vec![others[0].ext_id],
+                    true,
+                    ConflictPolicy::Warn,
+                    &fx.logger,
+                    &tx)?;
+
+    // only the organizer and attendees may check in
+    let result = Meeting::check_in(mtgs[1].ext_id, others[1].username.clone(), &fx.logger, &tx);
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::NotPermitted)));
+
+    let checked_in =
+        Meeting::check_in(mtgs[1].ext_id, others[0].username.clone(), &fx.logger, &tx)?;
+    assert_eq!(true, checked_in.checked_in_at.is_some());
+
+    // too early to check in to a meeting that is hours away
+    let later = Meeting::schedule_meeting(fx.user.username.clone(),
+                                          building.ext_id,
+                                          rooms[0].code.clone(),
+                                          "2029-10-11T13:00:00Z".to_string(),
+                                          "2029-10-11T14:00:00Z".to_string(),
+                                          "Later".to_string(),
+                                          &options,
+                                          &fx.logger,
+                                          &tx)?;
+    let result = Meeting::check_in(later.ext_id, fx.user.username.clone(), &fx.logger, &tx);
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::OutsideCheckInWindow)));
+
+    // once the grace period is up, it is too late to check in
+    let building =
+        Building::set_checkin_grace(building.ext_id, Duration::minutes(10), &fx.logger, &tx)?;
+    assert_eq!(10, building.checkin_grace_minutes);
+    let result = Meeting::check_in(mtgs[0].ext_id, fx.user.username.clone(), &fx.logger, &tx);
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::OutsideCheckInWindow)));
+
+    let released = Meeting::release_no_shows(&fx.logger, &tx)?;
+    assert_eq!(false, released.iter().any(|mtg| mtg.ext_id == mtgs[1].ext_id));
+    let released = released.into_iter()
+                           .find(|mtg| mtg.ext_id == mtgs[0].ext_id)
+                           .unwrap();
+
+    let result = Meeting::check_in(mtgs[0].ext_id, fx.user.username.clone(), &fx.logger, &tx);
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::NoShowReleased)));
+
+    // the rest of the released meeting's time slot is free again
+    let deadline = released.time_slot.upper().unwrap().value;
+    let result = Meeting::schedule_meeting(fx.user.username.clone(),
+                                           building.ext_id,
+                                           rooms[0].code.clone(),
+                                           deadline.to_rfc3339(),
+                                           (deadline + Duration::minutes(30)).to_rfc3339(),
+                                           "Walk-in".to_string(),
+                                           &options,
+                                           &fx.logger,
+                                           &tx);
+    assert_eq!(true, result.is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_room_buffers() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let room = Room::set_buffers(fx.building.ext_id,
+                                 fx.room.code.clone(),
+                                 Duration::minutes(15),
+                                 Duration::minutes(15),
+                                 &fx.logger,
+                                 &tx)?;
+    assert_eq!((15, 15), (room.setup_minutes, room.cleanup_minutes));
+
+    let mtg = fx.schedule("2018-10-12T09:00:00Z", "2018-10-12T10:00:00Z", &tx)?;
+    assert_eq!(timeslots::slot(Utc.with_ymd_and_hms(2018, 10, 12, 9, 0, 0).unwrap(),
+                               Utc.with_ymd_and_hms(2018, 10, 12, 10, 0, 0).unwrap()),
+               mtg.time_slot);
+
+    // the cleanup after the first meeting and the setup before the second
+    // one would overlap
+    let result = fx.schedule("2018-10-12T10:20:00Z", "2018-10-12T11:00:00Z", &tx);
+    assert_matches!(result,
+                    Err(MyError::MeetingError(MeetingError::ScheduleConflict)));
+
+    let result = fx.schedule("2018-10-12T10:30:00Z", "2018-10-12T11:00:00Z", &tx);
+    assert_eq!(true, result.is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_building_opening_hours() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let room = Room::get_rooms(&fx.logger, &tx)?.into_iter()
+                                                .find(|r| r.building_id == fx.building.id)
+                                                .unwrap();
+
+    let open = NaiveTime::from_hms_opt(8, 0, 0).unwrap();
+    let close = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+    let weekdays = vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri];
+    Building::set_opening_hours(fx.building.ext_id,
+                                weekdays.into_iter().map(|day| (day, open, close)).collect(),
+                                &fx.logger,
+                                &tx)?;
+    let hours = Building::add_closure(fx.building.ext_id,
+                                      NaiveDate::from_ymd_opt(2018, 12, 25).unwrap(),
+                                      "Christmas Day".to_string(),
+                                      &fx.logger,
+                                      &tx)?;
+    assert_eq!(5, hours.hours.len());
+    assert_eq!(vec![NaiveDate::from_ymd_opt(2018, 12, 25).unwrap()], hours.closed_on);
+
+    for &(start, end) in &[("2018-10-16T03:00:00Z", "2018-10-16T04:00:00Z"),
+                           ("2018-12-25T10:00:00Z", "2018-12-25T11:00:00Z")] {
+        let result = Meeting::schedule_meeting(fx.user.username.clone(),
+                                               fx.building.ext_id,
+                                               room.code.clone(),
+                                               start.to_string(),
+                                               end.to_string(),
+                                               "Meeting".to_string(),
+                                               &ScheduleOptions::default(),
+                                               &fx.logger,
+                                               &tx);
+        assert_matches!(result,
+                        Err(MyError::MeetingError(MeetingError::OutsideOpeningHours)));
+    }
+
+    Meeting::schedule_meeting(fx.user.username.clone(),
+                              fx.building.ext_id,
+                              room.code.clone(),
+                              "2018-10-16T10:00:00Z".to_string(),
+                              "2018-10-16T11:00:00Z".to_string(),
+                              "Meeting".to_string(),
+                              &ScheduleOptions::default(),
+                              &fx.logger,
+                              &tx)?;
+
+    let night: TSTZRange = range!('[' Utc.with_ymd_and_hms(2018, 10, 16, 3, 0, 0).unwrap(),
+                                      Utc.with_ymd_and_hms(2018, 10, 16, 4, 0, 0).unwrap(); ')');
+    let rooms = Room::find_available(fx.building.ext_id,
+                                     night,
+                                     &RoomFilter::default(),
+                                     &fx.logger,
+                                     &tx)?;
+    assert_eq!(true, rooms.is_empty());
+
+    let day: TSTZRange = range!('[' Utc.with_ymd_and_hms(2018, 10, 16, 0, 0, 0).unwrap(),
+                                    Utc.with_ymd_and_hms(2018, 10, 17, 0, 0, 0).unwrap(); ')');
+    let free_busy =
+        Room::get_free_busy(fx.building.ext_id, room.code.clone(), day, &fx.logger, &tx)?;
+    assert_eq!(Some(Utc.with_ymd_and_hms(2018, 10, 16, 8, 0, 0).unwrap()),
+               free_busy.free.first().and_then(|slot| slot.lower()).map(|bound| bound.value));
+    assert_eq!(Some(Utc.with_ymd_and_hms(2018, 10, 16, 18, 0, 0).unwrap()),
+               free_busy.free.last().and_then(|slot| slot.upper()).map(|bound| bound.value));
+
+    Ok(())
+}
+
+#[test]
+fn test_building_timezone() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let building = Building::set_timezone(fx.building.ext_id,
+                                          "Europe/Berlin".to_string(),
+                                          &fx.logger,
+                                          &tx)?;
+    assert_eq!("Europe/Berlin", building.timezone);
+
+    let result = Building::set_timezone(building.ext_id,
+                                        "Mars/Olympus_Mons".to_string(),
+                                        &fx.logger,
+                                        &tx);
+    assert_matches!(result, Err(MyError::ValueError(_)));
+
+    // local wall-clock times on either side of the end of DST
+    let mtg = fx.schedule("2018-10-27T09:00:00", "2018-10-27T10:00:00", &tx)?;
+    assert_eq!(Utc.with_ymd_and_hms(2018, 10, 27, 7, 0, 0).unwrap(),
+               mtg.time_slot.lower().unwrap().value);
+    assert_eq!("2018-10-27T09:00:00+02:00", mtg.local_start.to_rfc3339());
+
+    let mtg = fx.schedule("2018-10-29T09:00:00", "2018-10-29T10:00:00", &tx)?;
+    assert_eq!(Utc.with_ymd_and_hms(2018, 10, 29, 8, 0, 0).unwrap(),
+               mtg.time_slot.lower().unwrap().value);
+    assert_eq!("2018-10-29T10:00:00+01:00", mtg.local_end.to_rfc3339());
+
+    // an explicit offset is still accepted
+    let mtg = fx.schedule("2018-10-31T09:00:00-04:00", "2018-10-31T10:00:00-04:00", &tx)?;
+    assert_eq!("2018-10-31T14:00:00+01:00", mtg.local_start.to_rfc3339());
+
+    // 2:30 does not exist on the day DST starts
+    let result = fx.schedule("2019-03-31T02:30:00", "2019-03-31T03:30:00", &tx);
+    assert_matches!(result, Err(MyError::ValueError(_)));
+
+    // a weekly series keeps its local time across the change
+    let mut recurrence = Recurrence::new(Frequency::Weekly);
+    recurrence.count = Some(2);
+    let (_, occurrences) = MeetingSeries::schedule_series(fx.user.username.clone(),
+                                                          building.ext_id,
+                                                          fx.room.code.clone(),
+                                                          "2018-10-23T13:00:00".to_string(),
+                                                          "2018-10-23T14:00:00".to_string(),
+                                                          "Weekly".to_string(),
+                                                          recurrence,
+                                                          &fx.logger,
+                                                          &tx)?;
+    let local_starts = occurrences.into_iter()
+                                  .map(|occurrence| {
+                                      occurrence.result.unwrap().local_start.to_rfc3339()
+                                  })
+                                  .collect::<Vec<String>>();
+    assert_eq!(vec!["2018-10-23T13:00:00+02:00", "2018-10-30T13:00:00+01:00"], local_starts);
+
+    Ok(())
+}
+
+#[test]
+fn test_building_opening_windows() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    Building::set_timezone(fx.building.ext_id, "America/New_York".to_string(), &fx.logger, &tx)?;
+
+    // local days from Monday 2018-10-01 to Wednesday 2018-10-03, at UTC-4
+    let local = |day: u32, hour: u32| {
+        Utc.with_ymd_and_hms(2018, 10, day, hour, 0, 0).unwrap() + Duration::hours(4)
+    };
+    let window: TSTZRange = range!('[' local(1, 0), local(4, 0); ')');
+
+    // closed all Tuesday, otherwise open around the clock
+    Building::add_closure(fx.building.ext_id,
+                          NaiveDate::from_ymd_opt(2018, 10, 2).unwrap(),
+                          "Holiday".to_string(),
+                          &fx.logger,
+                          &tx)?;
+    let windows = Building::get_opening_windows(fx.building.ext_id, &window, &fx.logger, &tx)?;
+    assert_eq!(vec![range!('[' local(1, 0), local(2, 0); ')'),
+                    range!('[' local(3, 0), local(4, 0); ')')],
+               windows);
+
+    // open only on Mondays and Tuesdays, so just on Monday
+    let open = NaiveTime::from_hms_opt(8, 0, 0).unwrap();
+    let close = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+    Building::set_opening_hours(fx.building.ext_id,
+                                vec![(Weekday::Mon, open, close), (Weekday::Tue, open, close)],
+                                &fx.logger,
+                                &tx)?;
+    let windows = Building::get_opening_windows(fx.building.ext_id, &window, &fx.logger, &tx)?;
+    assert_eq!(vec![range!('[' local(1, 8), local(1, 18); ')')], windows);
+
+    let stmt = "
+		SELECT count(*)
+		  FROM testing.building b,
+			   testing.closed_periods(b.id, $2)
+		 WHERE b.ext_id = $1;";
+    let rows = tx.query(stmt, &[&fx.building.ext_id, &window])
+                 .map_err(|err| MyError::DBError(DBError::PGError(err)))?;
+    let closed: i64 = rows.get(0).get(0);
+    // before and after Monday's and Tuesday's hours, Tuesday's closure, and
+    // all of Wednesday
+    assert_eq!(6, closed);
+
+    // working hours follow the building's local time as well
+    let hours = WorkingHours::weekdays(NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                                       NaiveTime::from_hms_opt(17, 0, 0).unwrap());
+    let windows =
+        Building::get_working_windows(fx.building.ext_id, &hours, &window, &fx.logger, &tx)?;
+    assert_eq!(vec![range!('[' local(1, 9), local(1, 17); ')'),
+                    range!('[' local(2, 9), local(2, 17); ')'),
+                    range!('[' local(3, 9), local(3, 17); ')')],
+               windows);
+
+    Ok(())
+}
+
+#[test]
+fn test_mtg_list() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    // booked out of order
+    for &(start, end) in &[("2018-10-15T14:00:00Z", "2018-10-15T15:00:00Z"),
+                           ("2018-10-15T09:00:00Z", "2018-10-15T10:00:00Z"),
+                           ("2018-10-16T09:00:00Z", "2018-10-16T10:00:00Z")] {
+        fx.schedule(start, end, &tx)?;
+    }
+
+    let start = Utc.with_ymd_and_hms(2018, 10, 15, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2018, 10, 16, 0, 0, 0).unwrap();
+    let filter = MeetingFilter { organizer: Some(fx.user.username.clone()),
+                                 bldg_ext_id: Some(fx.building.ext_id),
+                                 room_code: Some(fx.room.code.clone()),
+                                 time_range: Some(range!('[' start, end; ')')) };
+    let mtgs = Meeting::list(&filter, &fx.logger, &tx)?;
+
+    assert_eq!(2, mtgs.len());
+    assert_eq!(Utc.with_ymd_and_hms(2018, 10, 15, 9, 0, 0).unwrap(),
+               mtgs[0].meeting.time_slot.lower().unwrap().value);
+    assert_eq!(Utc.with_ymd_and_hms(2018, 10, 15, 14, 0, 0).unwrap(),
+               mtgs[1].meeting.time_slot.lower().unwrap().value);
+    assert_eq!(true,
+               mtgs.iter().all(|mtg| mtg.room_code == fx.room.code
+                                     && mtg.building_name == fx.building.name));
+
+    Ok(())
+}
+
+#[test]
+fn test_mtg_room_availability_v2() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let mtg = fx.schedule("2018-09-26T09:00:00Z", "2018-09-26T10:00:00Z", &tx)?;
+
+    let preferred_timeslots: Vec<(i64, String, String)> =
+        vec![(26, "2018-09-26T09:30:00Z".to_string(), "2018-09-26T10:30:00Z".to_string()),
+             (27, "2018-09-26T10:00:00Z".to_string(), "2018-09-26T11:00:00Z".to_string())];
+
+    let result = Meeting::check_room_availability_v2(fx.room.code.clone(),
+                                                     fx.building.ext_id,
+                                                     preferred_timeslots,
+                                                     &[],
+                                                     &fx.logger,
+                                                     &tx)?;
+    assert_eq!(2, result.len());
+    assert_eq!((26, SlotStatus::Taken), (result[0].id, result[0].status));
+    assert_eq!(mtg.ext_id, result[0].conflicts[0].ext_id);
+    assert_eq!("Meeting", result[0].conflicts[0].title);
+    assert_eq!((27, SlotStatus::Free), (result[1].id, result[1].status));
+    assert_eq!(true, result[1].conflicts.is_empty());
+
+    let preferred_timeslots: Vec<(i64, String, String)> =
+        vec![(26, "2018-09-26T11:00:00Z".to_string(), "2018-09-26T12:00:00Z".to_string()),
+             (27, "not a time".to_string(), "2018-09-26T13:00:00Z".to_string())];
+
+    let result = Meeting::check_room_availability_v2(fx.room.code.clone(),
+                                                     fx.building.ext_id,
+                                                     preferred_timeslots.clone(),
+                                                     &[],
+                                                     &fx.logger,
+                                                     &tx);
+    assert_matches!(result, Err(MyError::ValueError(Some(1))));
+
+    let result = Meeting::check_room_availability_v1(fx.room.code.clone(),
+                                                     fx.building.ext_id,
+                                                     preferred_timeslots,
+                                                     &fx.logger,
+                                                     &tx);
+    assert_matches!(result, Err(MyError::ValueError(Some(1))));
+
+    Ok(())
+}
+
+#[test]
+fn test_mtg_schedule_many() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let request = |username: &str, room_code: &str, start_dt: &str, end_dt: &str, title: &str| {
+        BookingRequest { username: username.to_string(),
+                         bldg_ext_id: fx.building.ext_id,
+                         room_code: room_code.to_string(),
+                         start_dt: start_dt.to_string(),
+                         end_dt: end_dt.to_string(),
+                         title: title.to_string(),
+                         options: ScheduleOptions::default(), }
+    };
+
+    let requests = vec![request(&fx.user.username,
+                                &fx.room.code,
+                                "2018-09-27T09:00:00Z",
+                                "2018-09-27T10:00:00Z",
+                                "Meeting #1"),
+                        request(&fx.user.username,
+                                &fx.room.code,
+                                "2018-09-27T09:30:00Z",
+                                "2018-09-27T10:30:00Z",
+                                "Meeting #2"),
+                        request(&fx.user.username,
+                                "no such room",
+                                "2018-09-27T11:00:00Z",
+                                "2018-09-27T12:00:00Z",
+                                "Meeting #3"),
+                        request("no such user",
+                                &fx.room.code,
+                                "2018-09-27T11:00:00Z",
+                                "2018-09-27T12:00:00Z",
+                                "Meeting #4"),
+                        request(&fx.user.username,
+                                &fx.room.code,
+                                "2018-09-27T10:00:00Z",
+                                "2018-09-27T11:00:00Z",
+                                "Meeting #5")];
+
+    let results = Meeting::schedule_many(requests, &fx.logger, &tx)?;
+    assert_eq!(5, results.len());
+    assert_matches!(results[0], Ok(ref mtg) if mtg.title == "Meeting #1");
+    assert_matches!(results[1],
+                    Err(MyError::MeetingError(MeetingError::ScheduleConflict)));
+    assert_matches!(results[2], Err(MyError::MeetingError(MeetingError::RoomNotFound)));
+    assert_matches!(results[3], Err(MyError::MeetingError(MeetingError::UnknownUser)));
+    assert_matches!(results[4], Ok(ref mtg) if mtg.title == "Meeting #5");
+
+    // the failed requests did not roll back the ones booked around them
+    let start = Utc.with_ymd_and_hms(2018, 9, 27, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2018, 9, 28, 0, 0, 0).unwrap();
+    let filter = MeetingFilter { organizer: Some(fx.user.username.clone()),
+                                 time_range: Some(range!('[' start, end; ')')),
+                                 ..MeetingFilter::default() };
+    let titles = Meeting::list(&filter, &fx.logger, &tx)?.into_iter()
+                                                         .map(|details| details.meeting.title)
+                                                         .collect::<Vec<String>>();
+    assert_eq!(vec!["Meeting #1", "Meeting #5"], titles);
+
+    Ok(())
+}
+
+#[test]
+fn test_mtg_priority_bumping() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    User::set_admin(fx.user.username.clone(), false, &fx.logger, &tx)?;
+
+    let routine = fx.schedule("2018-09-28T09:00:00Z", "2018-09-28T10:00:00Z", &tx)?;
+    assert_eq!(Priority::Routine, routine.priority);
+
+    let options = ScheduleOptions { priority: Priority::Critical,
+                                    ..ScheduleOptions::default() };
+    let bump = |title: &str, start_dt: &str, end_dt: &str| {
+        Meeting::schedule_bumping(fx.user.username.clone(),
+                                  fx.building.ext_id,
+                                  fx.room.code.clone(),
+                                  start_dt.to_string(),
+                                  end_dt.to_string(),
+                                  title.to_string(),
+                                  &options,
+                                  &fx.logger,
+                                  &tx)
+    };
+
+    let result = bump("All-hands", "2018-09-28T09:30:00Z", "2018-09-28T11:00:00Z");
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::NotPermitted)));
+
+    // nor may a non-admin book at a raised priority without bumping
+    let result = fx.schedule_with("2018-09-28T13:00:00Z", "2018-09-28T14:00:00Z", &options, &tx);
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::NotPermitted)));
+
+    User::set_admin(fx.user.username.clone(), true, &fx.logger, &tx)?;
+
+    let (all_hands, bumped) = bump("All-hands", "2018-09-28T09:30:00Z", "2018-09-28T11:00:00Z")?;
+    assert_eq!(Priority::Critical, all_hands.priority);
+    assert_eq!(1, bumped.len());
+    assert_eq!(routine.ext_id, bumped[0].meeting.ext_id);
+    assert_eq!(fx.user.username, bumped[0].organizer);
+
+    // a meeting of the same priority is not bumped
+    let result = bump("Board meeting", "2018-09-28T10:30:00Z", "2018-09-28T12:00:00Z");
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::ScheduleConflict)));
+
+    let start = Utc.with_ymd_and_hms(2018, 9, 28, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2018, 9, 29, 0, 0, 0).unwrap();
+    let filter = MeetingFilter { bldg_ext_id: Some(fx.building.ext_id),
+                                 room_code: Some(fx.room.code.clone()),
+                                 time_range: Some(range!('[' start, end; ')')),
+                                 ..MeetingFilter::default() };
+    let titles = Meeting::list(&filter, &fx.logger, &tx)?.into_iter()
+                                                         .map(|details| details.meeting.title)
+                                                         .collect::<Vec<String>>();
+    assert_eq!(vec!["All-hands"], titles);
+
+    Ok(())
+}
+
+#[test]
+fn test_room_blackout() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    let blackout = Room::add_blackout(fx.building.ext_id,
+                                      fx.room.code.clone(),
+                                      "2018-10-03T09:00:00Z".to_string(),
+                                      "2018-10-03T12:00:00Z".to_string(),
+                                      "Replacing the carpet".to_string(),
+                                      &fx.logger,
+                                      &tx)?;
+
+    let schedule = |start_dt: &str, end_dt: &str| {
+        fx.schedule(start_dt, end_dt, &tx)
+    };
+
+    let result = schedule("2018-10-03T11:00:00Z", "2018-10-03T13:00:00Z");
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::RoomUnavailable)));
+
+    let mtg = schedule("2018-10-03T12:00:00Z", "2018-10-03T13:00:00Z")?;
+
+    // a blackout may not be added over a meeting
+    let result = Room::add_blackout(fx.building.ext_id,
+                                    fx.room.code.clone(),
+                                    "2018-10-03T12:30:00Z".to_string(),
+                                    "2018-10-03T14:00:00Z".to_string(),
+                                    "Painting".to_string(),
+                                    &fx.logger,
+                                    &tx);
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::ScheduleConflict)));
+
+    let start = Utc.with_ymd_and_hms(2018, 10, 3, 10, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2018, 10, 3, 11, 0, 0).unwrap();
+    let rooms = Room::find_available(fx.building.ext_id,
+                                     range!('[' start, end; ')'),
+                                     &RoomFilter::default(),
+                                     &fx.logger,
+                                     &tx)?;
+    assert_eq!(false, rooms.iter().any(|r| r.code == fx.room.code));
+
+    let preferred_timeslots: Vec<(i64, String, String)> =
+        vec![(1, "2018-10-03T10:00:00Z".to_string(), "2018-10-03T11:00:00Z".to_string())];
+    let result = Meeting::check_room_availability_v2(fx.room.code.clone(),
+                                                     fx.building.ext_id,
+                                                     preferred_timeslots,
+                                                     &[],
+                                                     &fx.logger,
+                                                     &tx)?;
+    assert_eq!(SlotStatus::Unavailable, result[0].status);
+
+    let start = Utc.with_ymd_and_hms(2018, 10, 3, 8, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2018, 10, 3, 14, 0, 0).unwrap();
+    let free_busy = Room::get_free_busy(fx.building.ext_id,
+                                        fx.room.code.clone(),
+                                        range!('[' start, end; ')'),
+                                        &fx.logger,
+                                        &tx)?;
+    assert_eq!(mtg.ext_id, free_busy.busy[0].meeting_ext_id);
+    assert_eq!(1, free_busy.blackouts.len());
+    assert_eq!(blackout.ext_id, free_busy.blackouts[0].ext_id);
+    assert_eq!(2, free_busy.free.len());
+
+    Room::remove_blackout(blackout.ext_id, &fx.logger, &tx)?;
+    schedule("2018-10-03T11:00:00Z", "2018-10-03T12:00:00Z")?;
+
+    Ok(())
+}
+
+#[test]
+fn test_room_blackout_concurrent() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+    let other_conn = get_conn()?;
+    let other_tx = other_conn.transaction()
+                             .map_err(|err| MyError::DBError(DBError::PGError(err)))?;
+
+
+    Room::add_blackout(fx.building.ext_id,
+                       fx.room.code.clone(),
+                       "2018-10-03T09:00:00Z".to_string(),
+                       "2018-10-03T12:00:00Z".to_string(),
+                       "Replacing the carpet".to_string(),
+                       &fx.logger,
+                       &tx)?;
+
+    // until the blackout is committed or rolled back, a booking of the same
+    // room in another transaction has to wait for it
+    other_tx.execute("SET LOCAL lock_timeout = '100ms';", &[])
+            .map_err(|err| MyError::DBError(DBError::PGError(err)))?;
+    let result = fx.schedule("2018-10-03T10:00:00Z", "2018-10-03T11:00:00Z", &other_tx);
+    assert_matches!(result, Err(MyError::DBError(DBError::PGError(_))));
+
+    Ok(())
+}
+
+#[test]
+fn test_room_amenities() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    Amenity::add_amenity("projector".to_string(), "Projector".to_string(), &fx.logger, &tx)?;
+    Amenity::add_amenity("whiteboard".to_string(), "Whiteboard".to_string(), &fx.logger, &tx)?;
+    let vc = Amenity::add_amenity("vc".to_string(),
+                                  "Video conferencing".to_string(),
+                                  &fx.logger,
+                                  &tx)?;
+    let vc = Amenity::rename_amenity(vc.code, "Video conference kit".to_string(), &fx.logger, &tx)?;
+    assert_eq!("Video conference kit", vc.name);
+
+    Room::add_amenity(fx.building.ext_id,
+                      fx.room.code.clone(),
+                      "projector".to_string(),
+                      &fx.logger,
+                      &tx)?;
+    Room::add_amenity(fx.building.ext_id,
+                      fx.room.code.clone(),
+                      "vc".to_string(),
+                      &fx.logger,
+                      &tx)?;
+    let amenities = Room::add_amenity(fx.building.ext_id,
+                                      fx.room.code.clone(),
+                                      "vc".to_string(),
+                                      &fx.logger,
+                                      &tx)?;
+    let codes = amenities.iter()
+                         .map(|a| a.code.as_str())
+                         .collect::<Vec<&str>>();
+    assert_eq!(vec!["projector", "vc"], codes);
+
+    let result = Room::add_amenity(fx.building.ext_id,
+                                   fx.room.code.clone(),
+                                   "hologram".to_string(),
+                                   &fx.logger,
+                                   &tx);
+    assert_matches!(result, Err(MyError::DBError(DBError::NoRecord)));
+
+    let start = Utc.with_ymd_and_hms(2018, 10, 4, 10, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2018, 10, 4, 11, 0, 0).unwrap();
+    let find = |amenities: Vec<&str>| {
+        let filters = RoomFilter { amenities: amenities.into_iter()
+                                                       .map(|a| a.to_string())
+                                                       .collect(),
+                                   ..RoomFilter::default() };
+        Room::find_available(fx.building.ext_id,
+                             range!('[' start, end; ')'),
+                             &filters,
+                             &fx.logger,
+                             &tx)
+    };
+
+    let rooms = find(vec!["projector", "vc"])?;
+    assert_eq!(true, rooms.iter().any(|r| r.code == fx.room.code));
+
+    let rooms = find(vec!["projector", "whiteboard"])?;
+    assert_eq!(false, rooms.iter().any(|r| r.code == fx.room.code));
+
+    // checking a single room takes its amenities into account as well
+    let preferred_timeslots: Vec<(i64, String, String)> =
+        vec![(1, "2018-10-04T10:00:00Z".to_string(), "2018-10-04T11:00:00Z".to_string())];
+    let result = Meeting::check_room_availability_v2(fx.room.code.clone(),
+                                                     fx.building.ext_id,
+                                                     preferred_timeslots,
+                                                     &["projector".to_string(),
+                                                       "whiteboard".to_string()],
+                                                     &fx.logger,
+                                                     &tx);
+    match result {
+        Err(MyError::MeetingError(MeetingError::MissingAmenities { missing })) => {
+            assert_eq!(vec!["whiteboard"], missing);
+        }
+        other => panic!("expected missing amenities, got {:?}", other),
+    }
+
+    let slots = Meeting::suggest_slots(fx.building.ext_id,
+                                       fx.room.code.clone(),
+                                       Duration::hours(1),
+                                       range!('[' start, end; ')'),
+                                       1,
+                                       None,
+                                       &["projector".to_string(), "vc".to_string()],
+                                       &fx.logger,
+                                       &tx)?;
+    assert_eq!(vec![range!('[' start, end; ')')], slots);
+
+    // removing an amenity from the catalog takes it away from the rooms too
+    Amenity::remove_amenity("vc".to_string(), &fx.logger, &tx)?;
+    let amenities = Room::remove_amenity(fx.building.ext_id,
+                                         fx.room.code.clone(),
+                                         "projector".to_string(),
+                                         &fx.logger,
+                                         &tx)?;
+    assert_eq!(true, amenities.is_empty());
+    let amenities = Room::get_amenities(fx.building.ext_id, fx.room.code.clone(), &fx.logger, &tx)?;
+    assert_eq!(true, amenities.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_room_type_rules() -> Result<(), MyError> {
+    let fx = Fixture::new()?;
+    let tx = fx.transaction()?;
+
+    User::set_admin(fx.user.username.clone(), false, &fx.logger, &tx)?;
+
+    let rules = BookingRules { max_booking_minutes: Some(60),
+                               advance_booking_days: Some(30),
+                               requires_approval: false, };
+    let booth = RoomType::add_room_type("booth".to_string(),
+                                        "Phone booth".to_string(),
+                                        &rules,
+                                        &fx.logger,
+                                        &tx)?;
+    let room = Room::set_room_type(fx.building.ext_id,
+                                   fx.room.code.clone(),
+                                   Some(booth.code.clone()),
+                                   &fx.logger,
+                                   &tx)?;
+    assert_eq!(Some(booth.id), room.room_type_id);
+
+    // booked for tomorrow, so that the booking window is not exceeded
+    let day = Utc.from_utc_datetime(&Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap())
+              + Duration::days(1);
+    let schedule = |start: DateTime<Utc>, end: DateTime<Utc>| {
+        fx.schedule(&start.to_rfc3339(), &end.to_rfc3339(), &tx)
+    };
+
+    let result = schedule(day + Duration::hours(9), day + Duration::hours(11));
+    assert_matches!(result,
+                    Err(MyError::MeetingError(MeetingError::BookingTooLong { max_minutes: 60 })));
+
+    let later = day + Duration::days(60);
+    let result = schedule(later + Duration::hours(9), later + Duration::hours(10));
+    assert_matches!(result,
+                    Err(MyError::MeetingError(MeetingError::OutsideBookingWindow {
+                                                  advance_days: 30,
+                                              })));
+
+    let mtg = schedule(day + Duration::hours(9), day + Duration::hours(10))?;
+    assert_eq!(MeetingStatus::Confirmed, mtg.status);
+
+    let rules = BookingRules { requires_approval: true,
+                               ..rules };
+    RoomType::set_rules(booth.code.clone(), &rules, &fx.logger, &tx)?;
+
+    let mtg = schedule(day + Duration::hours(10), day + Duration::hours(11))?;
+    assert_eq!(MeetingStatus::Pending, mtg.status);
+
+    let result = Meeting::approve(mtg.ext_id, fx.user.username.clone(), &fx.logger, &tx);
+    assert_matches!(result, Err(MyError::MeetingError(MeetingError::NotPermitted)));
+
+    User::set_admin(fx.user.username.clone(), true, &fx.logger, &tx)?;
+    let mtg = Meeting::approve(mtg.ext_id, fx.user.username.clone(), &fx.logger, &tx)?;
+    assert_eq!(MeetingStatus::Confirmed, mtg.status);
+
+    // the rules apply when moving a meeting too, and a moved meeting needs
+    // to be approved again
+    let reschedule = |start: DateTime<Utc>, end: DateTime<Utc>| {
+        Meeting::reschedule(mtg.ext_id,
+                            fx.user.username.clone(),
+                            room.code.clone(),
+                            fx.building.ext_id,
+                            start.to_rfc3339(),
+                            end.to_rfc3339(),
+                            &ScheduleOptions::default(),
+                            &fx.logger,
+                            &tx)
+    };
+    let result = reschedule(day + Duration::hours(11), day + Duration::hours(13));
+    assert_matches!(result,
+                    Err(MyError::MeetingError(MeetingError::BookingTooLong { max_minutes: 60 })));
+    let moved = reschedule(day + Duration::hours(11), day + Duration::hours(12))?;
+    assert_eq!(MeetingStatus::Pending, moved.status);
+
+    // a confirmed hold needs approval as well
+    let hold = Meeting::hold_room(fx.user.username.clone(),
+                                  fx.building.ext_id,
+                                  room.code.clone(),
+                                  (day + Duration::hours(13)).to_rfc3339(),
+                                  (day + Duration::hours(14)).to_rfc3339(),
+                                  "Call".to_string(),
+                                  Duration::minutes(15),
+                                  &ScheduleOptions::default(),
+                                  &fx.logger,
+                                  &tx)?;
+    assert_eq!(MeetingStatus::Hold, hold.status);
+    let hold = Meeting::confirm_hold(hold.ext_id, fx.user.username.clone(), &fx.logger, &tx)?;
+    assert_eq!(MeetingStatus::Pending, hold.status);
+
+    // without a type, no rules apply
+    Room::set_room_type(fx.building.ext_id, room.code.clone(), None, &fx.logger, &tx)?;
+    schedule(later + Duration::hours(9), later + Duration::hours(12))?;
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use chrono::prelude::*;
+
+use pg_example::{errors::MyError,
+                 recurrence::{Frequency, Recurrence, MAX_OCCURRENCES}};
+
+#[test]
+fn test_daily_count() {
+    let start = Utc.with_ymd_and_hms(2018, 10, 1, 9, 0, 0).unwrap();
+    let mut rule = Recurrence::new(Frequency::Daily);
+    rule.interval = 2;
+    rule.count = Some(3);
+
+    let starts = rule.occurrences(start);
+    assert_eq!(vec![start,
+                    Utc.with_ymd_and_hms(2018, 10, 3, 9, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2018, 10, 5, 9, 0, 0).unwrap()],
+               starts);
+}
+
+#[test]
+fn test_weekly_by_weekday_until() {
+    // 2018-10-03 is a Wednesday
+    let start = Utc.with_ymd_and_hms(2018, 10, 3, 9, 0, 0).unwrap();
+    let mut rule = Recurrence::new(Frequency::Weekly);
+    rule.by_weekday = vec![Weekday::Fri, Weekday::Mon, Weekday::Wed];
+    rule.until = Some(Utc.with_ymd_and_hms(2018, 10, 10, 23, 59, 59).unwrap());
+
+    let starts = rule.occurrences(start);
+    assert_eq!(vec![start,
+                    Utc.with_ymd_and_hms(2018, 10, 5, 9, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2018, 10, 8, 9, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2018, 10, 10, 9, 0, 0).unwrap()],
+               starts);
+}
+
+#[test]
+fn test_monthly_skips_short_months() {
+    let start = Utc.with_ymd_and_hms(2018, 12, 31, 15, 0, 0).unwrap();
+    let mut rule = Recurrence::new(Frequency::Monthly);
+    rule.count = Some(3);
+
+    let starts = rule.occurrences(start);
+    assert_eq!(vec![start,
+                    Utc.with_ymd_and_hms(2019, 1, 31, 15, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2019, 3, 31, 15, 0, 0).unwrap()],
+               starts);
+}
+
+#[test]
+fn test_count_limit() {
+    let mut rule = Recurrence::new(Frequency::Daily);
+    rule.count = Some(MAX_OCCURRENCES as u32);
+    assert_matches!(rule.validate(), Ok(()));
+
+    rule.count = Some(MAX_OCCURRENCES as u32 + 1);
+    assert_matches!(rule.validate(), Err(MyError::ValueError(None)));
+}
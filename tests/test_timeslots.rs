@@ -0,0 +1,76 @@
+use chrono::{prelude::*, Duration};
+
+use pg_example::{
+    db::TSTZRange,
+    timeslots::{candidate_slots, fit_slots, free_gaps, mask, shrink, slot},
+};
+
+fn at(hour: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2018, 10, 1, hour, min, 0).unwrap()
+}
+
+#[test]
+fn test_free_gaps() {
+    let window: TSTZRange = range!('[' at(9, 0), at(17, 0); ']');
+    let busy: Vec<TSTZRange> = vec![range!('[' at(16, 0), at(18, 0); ']'),
+                                    range!('[' at(10, 0), at(11, 0); ']'),
+                                    range!('[' at(10, 30), at(12, 0); ']')];
+
+    let gaps = free_gaps(&window, &busy);
+    assert_eq!(vec![range!('[' at(9, 0), at(10, 0); ')'),
+                    range!('(' at(12, 0), at(16, 0); ')')],
+               gaps);
+}
+
+#[test]
+fn test_free_gaps_without_busy() {
+    let window: TSTZRange = range!('[' at(9, 0), at(17, 0); ')');
+
+    assert_eq!(vec![window], free_gaps(&window, &[]));
+}
+
+#[test]
+fn test_free_gaps_fully_busy() {
+    let window: TSTZRange = range!('[' at(9, 0), at(17, 0); ')');
+    let busy: Vec<TSTZRange> = vec![range!('[' at(8, 0), at(18, 0); ')')];
+
+    assert_eq!(true, free_gaps(&window, &busy).is_empty());
+}
+
+#[test]
+fn test_fit_slots() {
+    let gaps: Vec<TSTZRange> = vec![range!('[' at(9, 0), at(9, 45); ')'),
+                                    range!('[' at(10, 0), at(13, 0); ')')];
+
+    let slots = fit_slots(&gaps, Duration::minutes(60), 2);
+    assert_eq!(vec![slot(at(10, 0), at(11, 0)), slot(at(11, 0), at(12, 0))], slots);
+}
+
+#[test]
+fn test_candidate_slots() {
+    let windows: Vec<TSTZRange> = vec![range!('[' at(9, 0), at(9, 45); ')'),
+                                       range!('[' at(10, 0), at(11, 30); ')')];
+
+    let slots = candidate_slots(&windows, Duration::minutes(60), Duration::minutes(15));
+    assert_eq!(vec![slot(at(10, 0), at(11, 0)),
+                    slot(at(10, 15), at(11, 15)),
+                    slot(at(10, 30), at(11, 30))],
+               slots);
+}
+
+#[test]
+fn test_shrink() {
+    let gap: TSTZRange = range!('(' at(10, 0), at(12, 0); ')');
+
+    assert_eq!(range!('(' at(10, 15), at(11, 45); ')'),
+               shrink(&gap, Duration::minutes(15), Duration::minutes(15)));
+    assert_eq!(true, shrink(&gap, Duration::minutes(60), Duration::minutes(60)).is_empty());
+}
+
+#[test]
+fn test_working_hours_mask() {
+    let windows: Vec<TSTZRange> = vec![range!('[' at(9, 0), at(17, 0); ')')];
+
+    let free: Vec<TSTZRange> = vec![range!('[' at(7, 0), at(10, 0); ')')];
+    assert_eq!(vec![range!('[' at(9, 0), at(10, 0); ')')], mask(&free, &windows));
+}